@@ -1,36 +1,463 @@
-use crate::{Table, Action}; 
+use crate::{Table, Action, Chips, Pot, Street}; 
+use std::fmt;
+use std::ops::RangeInclusive;
 
 /// Per-street betting state. 
 pub struct BetRound {
     /// how much each seat has put this steet so far 
-    contributed: Vec<u32>, 
-    current_bet: u32, 
-    opener: Option<usize>, 
-    last_to_act: usize, 
+    contributed: Vec<Chips>, 
+    current_bet: Chips, 
+    /// smallest opening bet (the big blind)
+    min_bet: Chips,
+    /// size of the last full bet or raise, which the next raise must at least match
+    last_raise: Chips,
+    /// bet level set by the last full bet or raise
+    full_raise_level: Chips,
+    /// per seat: the bet level and full-raise size when they last acted
+    acted: Vec<Option<(Chips, Chips)>>,
+    /// full bets and raises made this street, for the limit cap
+    bets: u8,
+    /// chips already in the pot from earlier streets
+    prior_pot: Chips,
+    /// antes each seat posted for themselves; they don't count as bets
+    antes: Vec<Chips>,
+    /// antes posted for the whole table (big blind or button ante)
+    dead_antes: Chips,
+    rules: RaiseRules,
+    structure: BetStructure,
 }
 
+/// How bets are sized. No-limit is the default.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum BetStructure {
+    #[default]
+    NoLimit,
+    FixedLimit(FixedLimit),
+    /// Bets and raises up to the size of the pot (after calling, for a raise).
+    PotLimit,
+}
+
+/// Fixed-limit sizing: every bet and raise is exactly one small bet preflop
+/// and on the flop, and one big bet on the turn and river.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FixedLimit {
+    pub small_bet: Chips,
+    pub big_bet: Chips,
+    /// Raises allowed after the opening bet; `None` means no cap.
+    pub max_raises: Option<u8>,
+    /// Lift the cap once only two players are left in the hand.
+    pub heads_up_uncapped: bool,
+}
+
+impl FixedLimit {
+    /// The usual structure: big bet twice the small bet, one bet and three
+    /// raises, no cap heads-up.
+    pub fn new(small_bet: Chips) -> Self {
+        Self { small_bet, big_bet: small_bet * 2, max_raises: Some(3), heads_up_uncapped: true }
+    }
+
+    /// Bet size on `street`.
+    pub fn bet_size(&self, street: Street) -> Chips {
+        match street {
+            Street::Preflop | Street::Flop => self.small_bet,
+            Street::Turn | Street::River | Street::Showdown => self.big_bet,
+        }
+    }
+}
+
+/// How short all-in raises are treated. The defaults follow the TDA rules: a
+/// short all-in doesn't reopen the betting for players who already acted,
+/// unless several of them add up to a full raise.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RaiseRules {
+    /// Short all-ins that together make a full raise reopen the betting for a
+    /// player who already acted. When false each all-in is judged on its own.
+    pub cumulative_all_ins: bool,
+    /// An all-in of at least half a full raise counts as a full raise for
+    /// reopening the betting (the "50% rule" some rooms use).
+    pub half_raise_reopens: bool,
+}
+
+impl Default for RaiseRules {
+    fn default() -> Self {
+        Self { cumulative_all_ins: true, half_raise_reopens: false }
+    }
+}
+
+/// What a seat may do right now. Amounts are in the units the matching
+/// `Action` takes: `Bet` is the total to put in, `Raise` is the increment
+/// on top of the current bet.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LegalActions {
+    pub fold: bool,
+    pub check: bool,
+    /// Chips it costs to call (less than the bet if calling puts them all-in).
+    pub call: Option<Chips>,
+    /// Allowed `Action::Bet` amounts when nobody has bet yet.
+    pub bet: Option<RangeInclusive<Chips>>,
+    /// Allowed `Action::Raise` increments when facing a bet.
+    pub raise: Option<RangeInclusive<Chips>>,
+    pub all_in: bool,
+}
+
+/// What happened after a valid action.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ActionOutcome {
+    /// The round goes on; this seat acts next.
+    NextToAct(usize),
+    /// Betting on this street is finished.
+    RoundOver,
+}
+
+/// Why an action was rejected. Nothing changes when an action is rejected.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ActionError {
+    /// There is no betting round in progress.
+    HandOver,
+    /// It is `to_act`'s turn, not this seat's.
+    NotYourTurn { to_act: usize },
+    /// Nobody sits in this seat.
+    EmptySeat,
+    /// The player has folded or is already all-in.
+    NotInHand,
+    /// Can't check while facing a bet.
+    IllegalCheck { to_call: Chips },
+    /// There is no bet to call.
+    NothingToCall,
+    /// Someone has already bet (raise instead), or nobody is left to bet against.
+    BetNotAllowed,
+    /// There is no bet to raise (bet instead), or nobody is left to raise against.
+    RaiseNotAllowed,
+    /// Bets and raises must be at least `min` unless they put the player all-in.
+    BetBelowMinimum { min: Chips },
+    /// The betting structure allows at most `max` for this bet or raise.
+    BetAboveMaximum { max: Chips },
+    /// The limit cap on raises has been reached this street.
+    BettingCapped,
+    /// The action needs more chips than the player has.
+    InsufficientChips { needed: Chips, available: Chips },
+    /// Players can still bet, so the board can't be run out yet.
+    BettingNotOver,
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::HandOver => write!(f, "no betting round in progress"),
+            ActionError::NotYourTurn { to_act } => write!(f, "it is seat {}'s turn", to_act),
+            ActionError::EmptySeat => write!(f, "seat is empty"),
+            ActionError::NotInHand => write!(f, "player has folded or is all-in"),
+            ActionError::IllegalCheck { to_call } => write!(f, "cannot check, {} to call", to_call),
+            ActionError::NothingToCall => write!(f, "there is no bet to call"),
+            ActionError::BetNotAllowed => write!(f, "cannot bet here"),
+            ActionError::RaiseNotAllowed => write!(f, "cannot raise here"),
+            ActionError::BetBelowMinimum { min } => write!(f, "minimum is {}", min),
+            ActionError::BetAboveMaximum { max } => write!(f, "maximum is {}", max),
+            ActionError::BettingCapped => write!(f, "betting is capped"),
+            ActionError::InsufficientChips { needed, available } => {
+                write!(f, "needs {} chips but only {} left", needed, available)
+            }
+            ActionError::BettingNotOver => write!(f, "betting is not over"),
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
 impl BetRound {
-    pub fn new(table: &Table, dealer_button: usize) -> Self {
+    pub fn new(table: &Table, big_blind: Chips) -> Self {
         let seats = table.seat_count(); 
-
-        let last = table.next_occupied(dealer_button); 
         Self {
-            contributed: vec![0; seats], 
-            current_bet: 0,
-            opener: None,
-            last_to_act: last,
+            contributed: vec![Chips::ZERO; seats], 
+            current_bet: Chips::ZERO,
+            min_bet: big_blind,
+            last_raise: big_blind,
+            full_raise_level: Chips::ZERO,
+            acted: vec![None; seats],
+            bets: 0,
+            prior_pot: Chips::ZERO,
+            antes: vec![Chips::ZERO; seats],
+            dead_antes: Chips::ZERO,
+            rules: RaiseRules::default(),
+            structure: BetStructure::NoLimit,
+        }
+    }
+
+    /// Size bets with `structure` for this `street` instead of no-limit.
+    pub fn with_structure(mut self, structure: BetStructure, street: Street) -> Self {
+        if let BetStructure::FixedLimit(limit) = structure {
+            self.min_bet = limit.bet_size(street);
+            self.last_raise = self.min_bet;
+        }
+        self.structure = structure;
+        self
+    }
+
+    /// Use these rules for short all-ins instead of the defaults.
+    pub fn with_rules(mut self, rules: RaiseRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Count `pot` chips from earlier streets towards pot-limit sizing.
+    pub fn with_prior_pot(mut self, pot: Chips) -> Self {
+        self.prior_pot = pot;
+        self
+    }
+
+    /// Everything in the pot, including bets made on this street.
+    pub fn pot(&self) -> Chips {
+        self.prior_pot
+            + self.contributed.iter().sum::<Chips>()
+            + self.antes.iter().sum::<Chips>()
+            + self.dead_antes
+    }
+
+    /// Post `seat_idx`'s own ante (or whatever they have left). Antes are dead
+    /// money: they go in the pot but aren't part of the player's bet. Returns
+    /// the amount posted.
+    pub fn post_ante(&mut self, table: &mut Table, seat_idx: usize, amount: Chips) -> Chips {
+        let posted = Self::take_chips(table, seat_idx, amount);
+        self.antes[seat_idx] += posted;
+        posted
+    }
+
+    /// Post an ante on behalf of the whole table, like the big-blind ante or
+    /// the short-deck button ante. It plays in the main pot.
+    pub fn post_table_ante(&mut self, table: &mut Table, seat_idx: usize, amount: Chips) -> Chips {
+        let posted = Self::take_chips(table, seat_idx, amount);
+        self.dead_antes += posted;
+        posted
+    }
+
+    /// Post a small or extra blind as a live bet (or whatever the player has left).
+    pub fn post_blind(&mut self, table: &mut Table, seat_idx: usize, amount: Chips) -> Chips {
+        let posted = Self::take_chips(table, seat_idx, amount);
+        self.contributed[seat_idx] += posted;
+        self.current_bet = self.current_bet.max(self.contributed[seat_idx]);
+        posted
+    }
+
+    /// Bring `seat_idx`'s live bet up to `amount`, a big blind they missed
+    /// while sitting out. What they already posted this hand, like the small
+    /// blind, counts towards it. Returns the amount added.
+    pub fn post_missed_blind(&mut self, table: &mut Table, seat_idx: usize, amount: Chips) -> Chips {
+        let owed = amount.saturating_sub(self.contributed[seat_idx]);
+        self.post_blind(table, seat_idx, owed)
+    }
+
+    /// Post the big blind as a live bet. The bet to call is the full big blind
+    /// even if the player is all-in for less, and it counts as the opening bet.
+    /// Like any blind it doesn't count as acting, so if nobody raises the big
+    /// blind still gets the option to check or raise.
+    pub fn post_big_blind(&mut self, table: &mut Table, seat_idx: usize, amount: Chips) -> Chips {
+        let posted = Self::take_chips(table, seat_idx, amount);
+        self.contributed[seat_idx] += posted;
+        self.current_bet = self.current_bet.max(amount);
+        self.full_raise_level = self.current_bet;
+        self.bets = 1;
+        posted
+    }
+
+    /// Post a voluntary straddle as a live blind. It becomes the bet to call,
+    /// the minimum raise becomes its size, and the straddler acts last preflop.
+    pub fn post_straddle(&mut self, table: &mut Table, seat_idx: usize, amount: Chips) -> Chips {
+        let posted = Self::take_chips(table, seat_idx, amount);
+        self.contributed[seat_idx] += posted;
+        self.current_bet = self.current_bet.max(amount);
+        self.last_raise = amount;
+        self.full_raise_level = self.current_bet;
+        self.bets += 1;
+        posted
+    }
+
+    fn take_chips(table: &mut Table, seat_idx: usize, amount: Chips) -> Chips {
+        let Some(player) = table.seat_mut(seat_idx) else { return Chips::ZERO };
+        let posted = amount.min(player.chips);
+        player.chips -= posted;
+        player.all_in = player.chips.is_zero();
+        posted
+    }
+
+    /// The pot-sized `Action::Raise` increment for `seat_idx` (or `Action::Bet`
+    /// amount with no bet out): the pot as it would be after they call.
+    pub fn pot_sized_increment(&self, seat_idx: usize) -> Chips {
+        self.pot() + self.current_bet - self.contributed[seat_idx]
+    }
+
+    /// Smallest legal raise increment: the last full raise, never below the big blind.
+    pub fn min_raise(&self) -> Chips {
+        self.last_raise.max(self.min_bet)
+    }
+
+    /// Whether `seat_idx` may still raise: they haven't acted yet, or the bet
+    /// has gone up by a full raise since they did.
+    pub fn may_raise(&self, seat_idx: usize) -> bool {
+        match self.acted[seat_idx] {
+            None => true,
+            Some((level, _)) if level < self.full_raise_level => true,
+            Some((level, min_raise)) => {
+                self.rules.cumulative_all_ins && self.current_bet - level >= min_raise
+            }
+        }
+    }
+
+    /// The bet every player has to match to stay in.
+    pub fn current_bet(&self) -> Chips {
+        self.current_bet
+    }
+
+    /// How much `seat_idx` has put in on this street.
+    pub fn contributed(&self, seat_idx: usize) -> Chips {
+        self.contributed[seat_idx]
+    }
+
+    /// Fill in the sizes the structure fixes: in limit the amounts passed to
+    /// `Bet` and `Raise` are ignored and replaced with the street's bet size.
+    fn resolve(&self, action: Action) -> Action {
+        match (self.structure, action) {
+            (BetStructure::FixedLimit(_), Action::Bet(_)) => Action::Bet(self.min_bet),
+            (BetStructure::FixedLimit(_), Action::Raise(_)) => Action::Raise(self.min_bet),
+            _ => action,
         }
     }
 
-    /// Apply an action, mutate player stacks, and return the seat index that
-    /// should act next (Or `None` if the round is finished)
+    /// Most a player facing `to_call` may add on top of the call.
+    fn max_increment(&self, to_call: Chips) -> Chips {
+        match self.structure {
+            BetStructure::NoLimit => Chips::MAX,
+            BetStructure::FixedLimit(_) => self.min_bet,
+            // never below the minimum, e.g. a big blind bet into an empty pot
+            BetStructure::PotLimit => (self.pot() + to_call).max(self.min_raise()),
+        }
+    }
+
+    /// Whether the limit cap on raises has been reached.
+    fn capped(&self, table: &Table) -> bool {
+        match self.structure {
+            BetStructure::NoLimit | BetStructure::PotLimit => false,
+            BetStructure::FixedLimit(limit) => {
+                let in_hand = (0..table.seat_count())
+                    .filter(|&i| table.seat(i).is_some_and(|p| !p.folded))
+                    .count();
+                let heads_up = limit.heads_up_uncapped && in_hand <= 2;
+                !heads_up && limit.max_raises.is_some_and(|max| self.bets > max)
+            }
+        }
+    }
+
+    /// Why `seat_idx` can't put in more than a call, if they can't.
+    fn raise_blocked(&self, table: &Table, seat_idx: usize) -> Option<ActionError> {
+        // no point betting if nobody else has chips left to answer
+        let others_can_act = (0..table.seat_count()).any(|i| {
+            i != seat_idx
+                && table.seat(i).is_some_and(|p| !p.folded && !p.all_in && !p.chips.is_zero())
+        });
+
+        if self.capped(table) {
+            Some(ActionError::BettingCapped)
+        } else if !others_can_act || !self.may_raise(seat_idx) {
+            // a short all-in may have left this player only able to call
+            Some(if self.current_bet.is_zero() { ActionError::BetNotAllowed } else { ActionError::RaiseNotAllowed })
+        } else {
+            None
+        }
+    }
+
+    /// List what `seat_idx` is allowed to do, with exact call and sizing limits.
+    /// Empty, folded and all-in seats can do nothing.
+    pub fn legal_actions(&self, table: &Table, seat_idx: usize) -> LegalActions {
+        let player = match table.seat(seat_idx) {
+            Some(p) if !p.folded && !p.all_in && !p.chips.is_zero() => p,
+            _ => return LegalActions::default(),
+        };
+
+        let to_call = self.current_bet - self.contributed[seat_idx];
+        let stack = player.chips;
+        let can_raise = self.raise_blocked(table, seat_idx).is_none() && stack > to_call;
+        let max_inc = self.max_increment(to_call);
+
+        let mut legal = LegalActions {
+            fold: true,
+            check: to_call.is_zero(),
+            call: (!to_call.is_zero()).then(|| to_call.min(stack)),
+            all_in: stack <= to_call || (can_raise && stack - to_call <= max_inc),
+            ..LegalActions::default()
+        };
+
+        if can_raise {
+            let min = if self.current_bet.is_zero() { self.min_bet } else { self.min_raise() };
+            let max = (stack - to_call).min(max_inc);
+            if stack - to_call >= min && min <= max {
+                if self.current_bet.is_zero() {
+                    legal.bet = Some(min..=max);
+                } else {
+                    legal.raise = Some(min..=max);
+                }
+            }
+        }
+        legal
+    }
+
+    /// Check `action` against the rules without changing anything.
+    pub fn validate(&self, table: &Table, seat_idx: usize, action: &Action) -> Result<(), ActionError> {
+        let player = table.seat(seat_idx).ok_or(ActionError::EmptySeat)?;
+        if player.folded || player.all_in {
+            return Err(ActionError::NotInHand);
+        }
+
+        let to_call = self.current_bet - self.contributed[seat_idx];
+        let chips = player.chips;
+        let max_inc = self.max_increment(to_call);
+
+        // how much goes in on top of the call, for anything bigger than a call
+        let increment = match self.resolve(*action) {
+            Action::Fold => return Ok(()),
+            Action::Check if to_call.is_zero() => return Ok(()),
+            Action::Check => return Err(ActionError::IllegalCheck { to_call }),
+            Action::Call if !to_call.is_zero() => return Ok(()),
+            Action::Call => return Err(ActionError::NothingToCall),
+            // going all-in for no more than a call is always allowed
+            Action::Allin if chips <= to_call => return Ok(()),
+            Action::Allin => chips - to_call,
+            Action::Bet(_) if !self.current_bet.is_zero() => return Err(ActionError::BetNotAllowed),
+            Action::Bet(amt) => amt,
+            Action::Raise(_) if self.current_bet.is_zero() || chips <= to_call => {
+                return Err(ActionError::RaiseNotAllowed);
+            }
+            Action::Raise(inc) => inc,
+        };
+
+        if let Some(err) = self.raise_blocked(table, seat_idx) {
+            return Err(err);
+        }
+        // a bot can ask for more chips than exist; that's just not enough chips
+        let needed = to_call.checked_add(increment).unwrap_or(Chips::MAX);
+        if needed > chips {
+            return Err(ActionError::InsufficientChips { needed, available: chips });
+        }
+        // betting your whole stack is fine even below the minimum
+        let min = if self.current_bet.is_zero() { self.min_bet } else { self.min_raise() };
+        if increment < min && to_call + increment < chips {
+            return Err(ActionError::BetBelowMinimum { min });
+        }
+        if increment > max_inc {
+            return Err(ActionError::BetAboveMaximum { max: max_inc });
+        }
+        Ok(())
+    }
+
+    /// Validate and apply an action, mutate player stacks, and say who acts
+    /// next. On error nothing is changed.
     pub fn act(
         &mut self, 
         table: &mut Table, 
         seat_idx: usize,
         action: Action,
-    ) -> Option<usize> {
-        let player = table.seat_mut(seat_idx).expect("empty seat cannot act"); 
+    ) -> Result<ActionOutcome, ActionError> {
+        let action = self.resolve(action);
+        self.validate(table, seat_idx, &action)?;
+        let player = table.seat_mut(seat_idx).ok_or(ActionError::EmptySeat)?; 
 
         match action {
 
@@ -45,7 +472,7 @@ impl BetRound {
                     Action::Call => self.current_bet,
                     Action::Allin => player.chips + self.contributed[seat_idx], 
                     Action::Bet(amt) => amt, 
-                    Action::Raise(inc) => self.current_bet + inc,
+                    Action::Raise(inc) => self.current_bet.checked_add(inc).unwrap_or(Chips::MAX),
                     _ => unreachable!(), 
                 };
 
@@ -55,65 +482,309 @@ impl BetRound {
                 // cap by their stack 
                 let pay = missing.min(player.chips); 
                 player.chips -= pay; 
+                player.all_in = player.chips.is_zero(); 
                 self.contributed[seat_idx] += pay;
 
                 // update current bet & opener
                 if self.contributed[seat_idx] > self.current_bet {
+                    let raise_by = self.contributed[seat_idx] - self.current_bet; 
+                    if raise_by >= self.min_raise() {
+                        // a full raise: sets the new minimum and reopens the betting
+                        self.last_raise = raise_by; 
+                        self.full_raise_level = self.contributed[seat_idx]; 
+                        self.bets += 1; 
+                    } else if self.rules.half_raise_reopens && raise_by * 2 >= self.min_raise() {
+                        self.full_raise_level = self.contributed[seat_idx]; 
+                        self.bets += 1; 
+                    }
                     self.current_bet = self.contributed[seat_idx]; 
-                    self.opener = Some(seat_idx); 
-                    self.last_to_act = table.next_occupied(seat_idx); 
                 }
             }  
 
         }
+        self.acted[seat_idx] = Some((self.current_bet, self.min_raise())); 
 
-        // is betting round closed? 
-        let mut idx = table.next_occupied(seat_idx); 
-        loop {
-            if idx == seat_idx {
-                break; // full - loop nobody left to act
-            }
+        match self.next_to_act(table, seat_idx) {
+            Some(idx) => Ok(ActionOutcome::NextToAct(idx)), 
+            None => Ok(ActionOutcome::RoundOver), 
+        }
+    }
 
-            let p = table.seat(idx).unwrap(); 
-            if !p.folded && self.contributed[idx] != self.current_bet {
-                return Some(idx); // someone still owes chips
-            }
+    /// Whether `seat_idx` still has to act this round: they are in the hand
+    /// with chips behind, and either haven't acted yet (blinds and straddles
+    /// included, which is their option) or face a bet they haven't called.
+    pub fn needs_to_act(&self, table: &Table, seat_idx: usize) -> bool {
+        let Some(p) = table.seat(seat_idx) else { return false };
+        !p.folded
+            && !p.all_in
+            && (self.acted[seat_idx].is_none() || self.contributed[seat_idx] < self.current_bet)
+    }
 
-            idx = table.next_occupied(idx); 
+    /// The first seat after `from`, going clockwise, that still has to act;
+    /// `None` once the round is over. Betting is also over when nobody is
+    /// left to bet against: one player in the hand, or only one with chips
+    /// behind who has nothing to call.
+    pub fn next_to_act(&self, table: &Table, from: usize) -> Option<usize> {
+        let in_hand = |i: usize| table.seat(i).is_some_and(|p| !p.folded); 
+        let can_bet = |i: usize| table.seat(i).is_some_and(|p| !p.folded && !p.all_in); 
+        let n = table.seat_count(); 
+        if (0..n).filter(|&i| in_hand(i)).count() < 2 {
+            return None; 
+        }
+        let mut with_chips = (0..n).filter(|&i| can_bet(i)); 
+        match (with_chips.next(), with_chips.next()) {
+            (None, _) => return None, 
+            (Some(only), None) if self.contributed[only] >= self.current_bet => return None, 
+            _ => {}
         }
 
-        // everybody matched (or folded) -> round over
-        None
+        (1..=n)
+            .map(|i| (from + i) % n)
+            .find(|&i| self.needs_to_act(table, i))
     }
 
-    /// Consume the BetRound and return a list of `(pot_size, winners_mask)` side-pots
-    pub fn into_sidepots(&self, table: &Table) -> Vec<(u32, Vec<usize>)> {
-        // gather (seat, contributed) for players still in hand
-        let mut pairs: Vec<(usize, u32)> = self
+    /// Give back the part of the biggest bet nobody matched, e.g. a bet
+    /// everyone folded to or an all-in bigger than any other stack. Call it
+    /// once the round is over. Returns the seat and amount returned, if any.
+    pub fn return_uncalled(&mut self, table: &mut Table) -> Option<(usize, Chips)> {
+        let (top, &most) = self.contributed.iter().enumerate().max_by_key(|&(_, c)| c)?;
+        let called = self
             .contributed
             .iter()
             .enumerate()
-            .filter(|(i, _)| table.seat(*i).map(|p| !p.folded).unwrap_or(false))
-            .map(|(i, &c)| (i, c))
-            .collect(); 
+            .filter(|&(i, _)| i != top)
+            .map(|(_, &c)| c)
+            .max()
+            .unwrap_or(Chips::ZERO);
+        if most <= called {
+            return None;
+        }
 
-        // sort by contribution (ascending) to peel side-pots
-        pairs.sort_by_key(|&(_, c)| c); 
+        let uncalled = most - called;
+        self.contributed[top] = called;
+        self.current_bet = self.current_bet.min(called);
+        let player = table.seat_mut(top)?;
+        player.chips += uncalled;
+        player.all_in = false;
+        Some((top, uncalled))
+    }
 
-        let mut pots = Vec::new(); 
-        let mut running_total = 0; 
+    /// Add this street's bets and antes to the hand's `Pot`.
+    pub fn collect(&self, pot: &mut Pot) {
+        for (seat, (&bet, &ante)) in self.contributed.iter().zip(&self.antes).enumerate() {
+            pot.add(seat, bet + ante);
+        }
+        pot.add_dead(self.dead_antes);
+    }
+}
 
-        while !pairs.is_empty() && pairs[0].1 > running_total {
-            let level = pairs[0].1; 
-            let level_size = level - running_total; 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            let contestants: Vec<usize> = pairs.iter().map(|&(i, _)| i).collect(); 
-            let pot_size = level_size * contestants.len() as u32; 
-            pots.push((pot_size, contestants)); 
+    #[test]
+    fn legal_actions_track_bets_and_raises() {
+        let mut table = Table::with_stacks(&[100, 100, 30]);
+        let mut round = BetRound::new(&table, Chips(2));
+
+        let opening = round.legal_actions(&table, 1);
+        assert!(opening.check && opening.call.is_none() && opening.raise.is_none());
+        assert_eq!(opening.bet, Some(Chips(2)..=Chips(100)));
+
+        round.act(&mut table, 1, Action::Bet(Chips(10))).unwrap();
+        let facing = round.legal_actions(&table, 2);
+        assert!(!facing.check && facing.bet.is_none());
+        assert_eq!(facing.call, Some(Chips(10)));
+        assert_eq!(facing.raise, Some(Chips(10)..=Chips(20)));
+
+        round.act(&mut table, 2, Action::Allin).unwrap();
+        let short = round.legal_actions(&table, 0);
+        assert_eq!(short.call, Some(Chips(30)));
+        assert_eq!(short.raise, Some(Chips(20)..=Chips(70)));
+        assert!(round.legal_actions(&table, 2) == LegalActions::default());
+    }
 
-            running_total = level;
-            pairs.remove(0);
+    #[test]
+    fn invalid_actions_are_rejected_without_changes() {
+        let mut table = Table::with_stacks(&[100, 100, 30]);
+        let mut round = BetRound::new(&table, Chips(2));
+
+        assert_eq!(round.act(&mut table, 1, Action::Call), Err(ActionError::NothingToCall));
+        assert_eq!(round.act(&mut table, 1, Action::Bet(Chips(1))), Err(ActionError::BetBelowMinimum { min: Chips(2) }));
+        assert_eq!(
+            round.act(&mut table, 1, Action::Bet(Chips(101))),
+            Err(ActionError::InsufficientChips { needed: Chips(101), available: Chips(100) })
+        );
+        round.act(&mut table, 1, Action::Bet(Chips(10))).unwrap();
+
+        assert_eq!(round.act(&mut table, 2, Action::Check), Err(ActionError::IllegalCheck { to_call: Chips(10) }));
+        assert_eq!(round.act(&mut table, 2, Action::Bet(Chips(20))), Err(ActionError::BetNotAllowed));
+        assert_eq!(table.seat(2).unwrap().chips, Chips(30));
+        assert_eq!(round.contributed(2), Chips(0));
+
+        round.act(&mut table, 2, Action::Allin).unwrap();
+        assert_eq!(round.act(&mut table, 2, Action::Fold), Err(ActionError::NotInHand));
+    }
+
+    #[test]
+    fn huge_bets_and_raises_are_rejected_instead_of_overflowing() {
+        let mut table = Table::with_stacks(&[100, 100, 100]);
+        let mut round = BetRound::new(&table, Chips(2));
+
+        let too_many = Err(ActionError::InsufficientChips { needed: Chips::MAX, available: Chips(100) });
+        assert_eq!(round.act(&mut table, 0, Action::Bet(Chips::MAX)), too_many);
+        round.act(&mut table, 0, Action::Bet(Chips(10))).unwrap();
+        assert_eq!(round.act(&mut table, 1, Action::Raise(Chips::MAX)), too_many);
+        assert_eq!(round.contributed(1), Chips(0));
+    }
+
+    #[test]
+    fn short_all_ins_only_reopen_when_they_add_up_to_a_full_raise() {
+        let play = |rules: RaiseRules| {
+            let mut table = Table::with_stacks(&[1000, 1000, 150, 220, 1000]);
+            let mut round = BetRound::new(&table, Chips(20)).with_rules(rules);
+            round.act(&mut table, 0, Action::Bet(Chips(100))).unwrap();
+            round.act(&mut table, 1, Action::Call).unwrap();
+            round.act(&mut table, 2, Action::Allin).unwrap(); // 150: short by 50
+
+            // the bettor only faces 50 more, so may call or fold
+            assert!(!round.may_raise(0));
+            assert_eq!(round.legal_actions(&table, 0).raise, None);
+            assert_eq!(round.legal_actions(&table, 4).raise, Some(Chips(100)..=Chips(850)));
+
+            round.act(&mut table, 3, Action::Allin).unwrap(); // 220: short again
+            assert_eq!(round.min_raise(), Chips(100));
+            round
+        };
+
+        // seat 1 called 100 and now faces 120 in total, a full raise
+        assert!(play(RaiseRules::default()).may_raise(1));
+        assert!(!play(RaiseRules { cumulative_all_ins: false, ..RaiseRules::default() }).may_raise(1));
+    }
+
+    #[test]
+    fn fixed_limit_sizes_bets_and_caps_raises() {
+        let mut table = Table::with_stacks(&[100, 100, 100]);
+        let limit = BetStructure::FixedLimit(FixedLimit::new(Chips(4)));
+        let mut round = BetRound::new(&table, Chips(4)).with_structure(limit, Street::Turn);
+
+        assert_eq!(round.legal_actions(&table, 1).bet, Some(Chips(8)..=Chips(8)));
+        round.act(&mut table, 1, Action::Bet(Chips(0))).unwrap();
+        assert_eq!(round.current_bet(), Chips(8));
+        round.act(&mut table, 2, Action::Raise(Chips(50))).unwrap();
+        assert_eq!(round.current_bet(), Chips(16));
+        round.act(&mut table, 0, Action::Raise(Chips(0))).unwrap();
+        assert_eq!(round.act(&mut table, 1, Action::Allin), Err(ActionError::BetAboveMaximum { max: Chips(8) }));
+        round.act(&mut table, 1, Action::Raise(Chips(0))).unwrap();
+
+        // one bet and three raises: capped
+        assert_eq!(round.current_bet(), Chips(32));
+        assert_eq!(round.act(&mut table, 2, Action::Raise(Chips(0))), Err(ActionError::BettingCapped));
+        assert_eq!(round.legal_actions(&table, 2).call, Some(Chips(16)));
+
+        // heads-up there is no cap
+        round.act(&mut table, 2, Action::Fold).unwrap();
+        assert_eq!(round.legal_actions(&table, 0).raise, Some(Chips(8)..=Chips(8)));
+    }
+
+    #[test]
+    fn pot_limit_counts_earlier_streets_and_the_call() {
+        let mut table = Table::with_stacks(&[1000, 1000, 1000]);
+        let mut round = BetRound::new(&table, Chips(10))
+            .with_structure(BetStructure::PotLimit, Street::Flop)
+            .with_prior_pot(Chips(100));
+
+        assert_eq!(round.legal_actions(&table, 1).bet, Some(Chips(10)..=Chips(100)));
+        assert_eq!(round.act(&mut table, 1, Action::Bet(Chips(101))), Err(ActionError::BetAboveMaximum { max: Chips(100) }));
+        round.act(&mut table, 1, Action::Bet(Chips(100))).unwrap();
+
+        // call 100, then the pot is 300: raise to 400 at most
+        assert_eq!(round.pot_sized_increment(2), Chips(300));
+        assert_eq!(round.legal_actions(&table, 2).raise, Some(Chips(100)..=Chips(300)));
+        assert!(!round.legal_actions(&table, 2).all_in);
+    }
+
+    #[test]
+    fn antes_are_dead_money_in_side_pots() {
+        let mut table = Table::with_stacks(&[100, 100, 30]);
+        let mut round = BetRound::new(&table, Chips(10));
+        for seat in 0..3 {
+            round.post_ante(&mut table, seat, Chips(5));
         }
-        pots
+        round.post_table_ante(&mut table, 0, Chips(10));
+        assert_eq!(round.pot(), Chips(25));
+        assert_eq!(round.current_bet(), Chips(0));
+
+        round.act(&mut table, 1, Action::Bet(Chips(40))).unwrap();
+        round.act(&mut table, 2, Action::Allin).unwrap(); // 25 behind the ante
+        round.act(&mut table, 0, Action::Fold).unwrap();
+
+        // main: 30 from each live player + seat 0's ante + the table ante
+        let mut pot = Pot::new(3);
+        round.collect(&mut pot);
+        let pots: Vec<(Chips, Vec<usize>)> = pot.side_pots(&table).into_iter().map(|p| (p.amount, p.eligible)).collect();
+        assert_eq!(pots, vec![(Chips(75), vec![1, 2]), (Chips(15), vec![1])]);
+        assert_eq!(pot.total(), round.pot());
+    }
+
+    #[test]
+    fn big_blind_is_a_live_bet_with_the_option() {
+        let mut table = Table::with_stacks(&[100, 100, 100]);
+        let mut round = BetRound::new(&table, Chips(2));
+        round.post_blind(&mut table, 1, Chips(1));
+        round.post_big_blind(&mut table, 2, Chips(2));
+        assert_eq!(round.current_bet(), Chips(2));
+        assert_eq!(round.legal_actions(&table, 0).call, Some(Chips(2)));
+        assert_eq!(round.legal_actions(&table, 1).call, Some(Chips(1)));
+
+        assert_eq!(round.act(&mut table, 0, Action::Call), Ok(ActionOutcome::NextToAct(1)));
+        assert_eq!(round.act(&mut table, 1, Action::Call), Ok(ActionOutcome::NextToAct(2)));
+        assert!(round.legal_actions(&table, 2).check);
+        assert_eq!(round.act(&mut table, 2, Action::Check), Ok(ActionOutcome::RoundOver));
+        assert_eq!(round.pot(), Chips(6));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn short_big_blind_still_sets_the_full_bet() {
+        let mut table = Table::with_stacks(&[100, 100, 1]);
+        let mut round = BetRound::new(&table, Chips(2));
+        round.post_blind(&mut table, 1, Chips(1));
+        assert_eq!(round.post_big_blind(&mut table, 2, Chips(2)), Chips(1));
+        assert!(table.seat(2).unwrap().all_in);
+        assert_eq!(round.legal_actions(&table, 0).call, Some(Chips(2)));
+    }
+
+    #[test]
+    fn straddle_sets_the_bet_and_takes_the_option() {
+        let mut table = Table::with_stacks(&[100, 100, 100, 100]);
+        let mut round = BetRound::new(&table, Chips(2));
+        round.post_blind(&mut table, 1, Chips(1));
+        round.post_big_blind(&mut table, 2, Chips(2));
+        round.post_straddle(&mut table, 3, Chips(4));
+
+        assert_eq!(round.current_bet(), Chips(4));
+        assert_eq!(round.legal_actions(&table, 0).raise, Some(Chips(4)..=Chips(96)));
+        round.act(&mut table, 0, Action::Call).unwrap();
+        round.act(&mut table, 1, Action::Call).unwrap();
+        assert_eq!(round.act(&mut table, 2, Action::Call), Ok(ActionOutcome::NextToAct(3)));
+        assert!(round.legal_actions(&table, 3).check);
+    }
+
+    #[test]
+    fn uncalled_part_of_a_bet_goes_back() {
+        let mut table = Table::with_stacks(&[100, 40, 100]);
+        let mut round = BetRound::new(&table, Chips(2));
+        round.act(&mut table, 0, Action::Bet(Chips(10))).unwrap();
+        round.act(&mut table, 1, Action::Allin).unwrap();
+        round.act(&mut table, 2, Action::Raise(Chips(60))).unwrap();
+        round.act(&mut table, 0, Action::Fold).unwrap();
+
+        // seat 2 raised to 100 but only 40 could be called
+        assert_eq!(round.return_uncalled(&mut table), Some((2, Chips(60))));
+        assert_eq!(table.seat(2).unwrap().chips, Chips(60));
+        assert_eq!(round.return_uncalled(&mut table), None);
+        let mut pot = Pot::new(3);
+        round.collect(&mut pot);
+        assert_eq!(pot.total(), Chips(90));
+    }
+}
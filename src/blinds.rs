@@ -0,0 +1,132 @@
+//! blinds.rs - where the button and blinds go from one hand to the next
+
+use crate::Table;
+
+/// How the button moves when players leave, sit out or join.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ButtonRule {
+    /// The button always moves to the next player dealt in, and the blinds
+    /// follow it. Simple, but a player can end up skipping a blind.
+    #[default]
+    Moving,
+    /// The big blind always moves forward one player, and the small blind and
+    /// button follow where it was. That can leave the button on an empty seat
+    /// (a dead button) or nobody to post the small blind (a dead small blind),
+    /// but nobody ever skips the big blind.
+    Dead,
+}
+
+/// Button and blind seats for one hand.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Positions {
+    /// May be an empty seat under `ButtonRule::Dead`.
+    pub button: usize,
+    /// `None` when the small blind is dead.
+    pub small_blind: Option<usize>,
+    pub big_blind: usize,
+    /// Where the small blind was, posted or not; the button goes here next.
+    small_blind_seat: usize,
+}
+
+impl Positions {
+    /// Work out the positions for the next hand and move `table.dealer_button`
+    /// there. Players sitting out who the big blind passed over are marked as
+    /// owing it, as is a player sitting out in a dead small blind.
+    pub fn next(table: &mut Table, rule: ButtonRule, last: Option<Positions>) -> Positions {
+        let positions = match (rule, last) {
+            (ButtonRule::Dead, Some(last)) if table.deal_order().len() > 2 => {
+                let big_blind = table.next_occupied(last.big_blind);
+                let sb_seat = last.big_blind;
+                Positions {
+                    button: last.small_blind_seat,
+                    small_blind: table.is_dealt_in(sb_seat).then_some(sb_seat),
+                    big_blind,
+                    small_blind_seat: sb_seat,
+                }
+            }
+            _ => Self::moving(table, last),
+        };
+        table.dealer_button = positions.button;
+
+        if let Some(last) = last {
+            // everyone sitting out between the old and the new big blind missed it
+            let n = table.seat_count();
+            let mut seat = (last.big_blind + 1) % n;
+            while seat != positions.big_blind && seat != last.big_blind {
+                if let Some(p) = table.seat_mut(seat).filter(|p| p.sitting_out) {
+                    p.missed_big_blind = true;
+                    p.missed_small_blind = true;
+                }
+                seat = (seat + 1) % n;
+            }
+            let dead_small = positions.small_blind.is_none();
+            if let Some(p) = table.seat_mut(positions.small_blind_seat).filter(|p| dead_small && p.sitting_out) {
+                p.missed_small_blind = true;
+            }
+        }
+        positions
+    }
+
+    /// The button moves one player on; the two players after it post. Heads-up
+    /// the button posts the small blind instead.
+    fn moving(table: &mut Table, last: Option<Positions>) -> Positions {
+        table.advance_button();
+        let button = table.dealer_button;
+
+        if table.deal_order().len() != 2 {
+            let sb = table.next_occupied(button);
+            return Positions {
+                button,
+                small_blind: Some(sb),
+                big_blind: table.next_occupied(sb),
+                small_blind_seat: sb,
+            };
+        }
+
+        // just gone heads-up: whoever had the big blind last hand takes the
+        // button rather than posting it twice in a row
+        let mut button = button;
+        if last.map(|l| l.big_blind) == Some(table.next_occupied(button)) {
+            button = table.next_occupied(button);
+        }
+        Positions {
+            button,
+            small_blind: Some(button),
+            big_blind: table.next_occupied(button),
+            small_blind_seat: button,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_button_keeps_the_big_blind_moving_one_seat() {
+        let mut table = Table::seated(6, &[100; 4]);
+        let first = Positions::next(&mut table, ButtonRule::Dead, None);
+        assert_eq!((first.button, first.small_blind, first.big_blind), (1, Some(2), 3));
+
+        // the big blind leaves: the small blind is dead next hand, then the button
+        table.remove_player(3);
+        let second = Positions::next(&mut table, ButtonRule::Dead, Some(first));
+        assert_eq!((second.button, second.small_blind, second.big_blind), (2, None, 0));
+        let third = Positions::next(&mut table, ButtonRule::Dead, Some(second));
+        assert_eq!((third.button, third.small_blind, third.big_blind), (3, Some(0), 1));
+        assert_eq!(table.dealer_button, 3);
+    }
+
+    #[test]
+    fn sitting_out_through_the_big_blind_owes_both_blinds() {
+        let mut table = Table::seated(6, &[100; 4]);
+        let first = Positions::next(&mut table, ButtonRule::Moving, None); // BB 3
+        table.seat_mut(0).unwrap().sitting_out = true;
+
+        let second = Positions::next(&mut table, ButtonRule::Moving, Some(first));
+        assert_eq!(second.big_blind, 1);
+        let p = table.seat(0).unwrap();
+        assert!(p.missed_big_blind && p.missed_small_blind);
+        assert!(!table.seat(3).unwrap().missed_big_blind);
+    }
+}
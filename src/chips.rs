@@ -0,0 +1,214 @@
+//! chips.rs - chip amounts and what they are worth
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Rem, Sub, SubAssign};
+
+/// A number of chips. The operators panic instead of wrapping if a count
+/// would go negative or overflow, which can only be a bookkeeping bug; use
+/// `checked_add`/`checked_sub` where running out is expected.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Chips(pub u32);
+
+impl Chips {
+    pub const ZERO: Chips = Chips(0);
+    pub const MAX: Chips = Chips(u32::MAX);
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, other: Chips) -> Option<Chips> {
+        self.0.checked_add(other.0).map(Chips)
+    }
+
+    pub fn checked_sub(self, other: Chips) -> Option<Chips> {
+        self.0.checked_sub(other.0).map(Chips)
+    }
+
+    pub fn saturating_sub(self, other: Chips) -> Chips {
+        Chips(self.0.saturating_sub(other.0))
+    }
+}
+
+impl From<u32> for Chips {
+    fn from(n: u32) -> Self {
+        Chips(n)
+    }
+}
+
+impl fmt::Display for Chips {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Add for Chips {
+    type Output = Chips;
+    fn add(self, other: Chips) -> Chips {
+        self.checked_add(other).expect("chip count overflowed")
+    }
+}
+
+impl Sub for Chips {
+    type Output = Chips;
+    fn sub(self, other: Chips) -> Chips {
+        self.checked_sub(other).expect("chip count went negative")
+    }
+}
+
+impl AddAssign for Chips {
+    fn add_assign(&mut self, other: Chips) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Chips {
+    fn sub_assign(&mut self, other: Chips) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<u32> for Chips {
+    type Output = Chips;
+    fn mul(self, n: u32) -> Chips {
+        Chips(self.0.checked_mul(n).expect("chip count overflowed"))
+    }
+}
+
+impl MulAssign<u32> for Chips {
+    fn mul_assign(&mut self, n: u32) {
+        *self = *self * n;
+    }
+}
+
+impl Div<u32> for Chips {
+    type Output = Chips;
+    fn div(self, n: u32) -> Chips {
+        Chips(self.0 / n)
+    }
+}
+
+impl Rem<u32> for Chips {
+    type Output = Chips;
+    fn rem(self, n: u32) -> Chips {
+        Chips(self.0 % n)
+    }
+}
+
+impl Sum for Chips {
+    fn sum<I: Iterator<Item = Chips>>(iter: I) -> Chips {
+        iter.fold(Chips::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Chips> for Chips {
+    fn sum<I: Iterator<Item = &'a Chips>>(iter: I) -> Chips {
+        iter.copied().sum()
+    }
+}
+
+/// What one chip is worth in real money.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Denomination {
+    /// Shown after amounts, e.g. "USD".
+    pub currency: String,
+    /// Digits after the decimal point (2 for cents).
+    pub decimals: u8,
+    /// Value of one chip in the currency's smallest unit.
+    pub chip_value: u64,
+}
+
+impl Default for Denomination {
+    /// Plain chips, worth one each.
+    fn default() -> Self {
+        Self::new("chips", 0, 1)
+    }
+}
+
+impl Denomination {
+    pub fn new(currency: impl Into<String>, decimals: u8, chip_value: u64) -> Self {
+        Self { currency: currency.into(), decimals, chip_value }
+    }
+
+    /// `chips` in the currency's smallest unit (e.g. cents).
+    pub fn to_minor_units(&self, chips: Chips) -> u64 {
+        chips.0 as u64 * self.chip_value
+    }
+
+    /// The chips making up `amount` smallest units, if it is a whole number of them.
+    pub fn to_chips(&self, amount: u64) -> Option<Chips> {
+        if self.chip_value == 0 || !amount.is_multiple_of(self.chip_value) {
+            return None;
+        }
+        u32::try_from(amount / self.chip_value).ok().map(Chips)
+    }
+
+    /// `chips` as money, e.g. "0.25 USD".
+    pub fn format(&self, chips: Chips) -> String {
+        let minor = self.to_minor_units(chips);
+        if self.decimals == 0 {
+            return format!("{} {}", minor, self.currency);
+        }
+        let scale = 10u64.pow(self.decimals as u32);
+        format!(
+            "{}.{:0width$} {}",
+            minor / scale,
+            minor % scale,
+            self.currency,
+            width = self.decimals as usize
+        )
+    }
+}
+
+/// Blinds for a game and what its chips are worth.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stakes {
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub denomination: Denomination,
+}
+
+impl Stakes {
+    /// Stakes from blinds in the currency's smallest unit, e.g. 25 and 50
+    /// cents. `None` if a blind isn't a whole number of chips.
+    pub fn from_money(denomination: Denomination, small_blind: u64, big_blind: u64) -> Option<Self> {
+        Some(Self {
+            small_blind: denomination.to_chips(small_blind)?,
+            big_blind: denomination.to_chips(big_blind)?,
+            denomination,
+        })
+    }
+}
+
+impl fmt::Display for Stakes {
+    /// e.g. "0.25/0.50 USD".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let small = self.denomination.format(self.small_blind);
+        let big = self.denomination.format(self.big_blind);
+        let small = small.trim_end_matches(self.denomination.currency.as_str()).trim_end();
+        write!(f, "{}/{}", small, big)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarter_half_stakes_in_cent_chips() {
+        let cents = Denomination::new("USD", 2, 1);
+        let stakes = Stakes::from_money(cents.clone(), 25, 50).unwrap();
+        assert_eq!(stakes.big_blind, Chips(50));
+        assert_eq!(stakes.to_string(), "0.25/0.50 USD");
+        assert_eq!(cents.format(Chips(1234)), "12.34 USD");
+
+        // quarter chips can't make a 10c ante
+        let quarters = Denomination::new("USD", 2, 25);
+        assert_eq!(quarters.to_chips(10), None);
+        assert_eq!(quarters.to_chips(50), Some(Chips(2)));
+
+        assert_eq!(Chips(5).checked_sub(Chips(6)), None);
+        assert_eq!([Chips(1), Chips(2)].iter().sum::<Chips>(), Chips(3));
+    }
+}
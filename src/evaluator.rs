@@ -1,7 +1,8 @@
 // we may want to expand this with more possible evaluations
-use crate::{Card, Rank}; 
+use crate::{Card, DeckConfig, DeckError, DeckKind, Rank, Suit}; 
+use itertools::Itertools; 
 
-/// The nine hand types ranked from weakest to strongest
+/// The ten hand types ranked from weakest to strongest
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum HandCategory {
     HighCard = 0, 
@@ -13,19 +14,25 @@ pub enum HandCategory {
     FullHouse = 6, 
     FourOfAKind = 7, 
     StraightFlush = 8, 
+    /// Only possible with wild cards or a multi-deck shoe.
+    FiveOfAKind = 9,
 }
 
 /// A fully ordered score: higher compares > lower. 
-#[derive(Eq, PartialEq, Debug)]
+///
+/// Only compare ranks produced by the same `Evaluator`.
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct HandRank {
     pub category: HandCategory, 
     kickers: [Rank; 5],
+    /// category position under the evaluator's rules (short decks reorder some)
+    strength: u8,
 }
 
 impl Ord for HandRank {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.category
-            .cmp(&other.category)
+        self.strength
+            .cmp(&other.strength)
             .then_with(|| self.kickers.cmp(&other.kickers))
     }
 }
@@ -35,21 +42,199 @@ impl PartialOrd for HandRank {
     }
 }
 
-/// Evaluate the best 5-card hand out of 7. 
-pub fn evaluate_seven(cards: &[Card; 7]) -> HandRank {
-    use itertools::Itertools; 
-
-    cards
-        .iter()
-        .copied()
-        .combinations(5)
-        .map(|c5| evaluate_five(&c5))
-        .max()
-        .unwrap()
+/// Evaluate the best 5-card hand out of 7 with standard rankings. Jokers
+/// aren't wild here, so a hand holding one is an error.
+pub fn evaluate_seven(cards: &[Card; 7]) -> Result<HandRank, DeckError> {
+    Evaluator::default().evaluate_seven(cards)
 }
 
-/// Core evaluator for exactly 5 cards.
-fn evaluate_five(cards: &[Card]) -> HandRank {
+/// Which cards are wild.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct WildCards {
+    /// Jokers are wild.
+    pub jokers: bool,
+    /// Every card of this rank is wild, e.g. `Some(Rank::Two)` for deuces wild.
+    pub rank: Option<Rank>,
+    /// "The bug": a joker only counts as an Ace, or to complete a straight,
+    /// flush or straight flush. Wild cards of `rank` are unaffected.
+    pub bug: bool,
+}
+
+impl WildCards {
+    pub fn jokers() -> Self {
+        Self { jokers: true, ..Self::default() }
+    }
+
+    pub fn rank(rank: Rank) -> Self {
+        Self { rank: Some(rank), ..Self::default() }
+    }
+
+    fn any(&self) -> bool {
+        self.jokers || self.rank.is_some()
+    }
+}
+
+/// The best hand made with wild cards, and what each wild card stood for.
+#[derive(Debug)]
+pub struct WildHand {
+    pub rank: HandRank,
+    /// `(wild card, card it replaced)` pairs.
+    pub substitutions: Vec<(Card, Card)>,
+}
+
+/// Hand-ranking rules for the deck in play.
+///
+/// Stripped decks change two things: the lowest straight is the Ace plus the
+/// four lowest ranks (A-6-7-8-9 in short deck), and since flushes get rarer
+/// than full houses, a flush beats a full house.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Evaluator {
+    kind: DeckKind,
+    wild: WildCards,
+}
+
+impl Evaluator {
+    pub fn new(kind: DeckKind) -> Self {
+        Self { kind, wild: WildCards::default() }
+    }
+
+    /// Play with wild cards.
+    pub fn with_wild(mut self, wild: WildCards) -> Self {
+        self.wild = wild;
+        self
+    }
+
+    pub fn kind(&self) -> DeckKind {
+        self.kind
+    }
+
+    pub fn wild(&self) -> WildCards {
+        self.wild
+    }
+
+    /// Check that every hand this deck can produce can be ranked.
+    pub fn check_deck(&self, config: &DeckConfig) -> Result<(), DeckError> {
+        config.validate()?;
+        if config.kind != self.kind {
+            return Err(DeckError::WrongKind { deck: config.kind, evaluator: self.kind });
+        }
+        if config.jokers > 0 && !self.wild.jokers {
+            return Err(DeckError::JokersNotSupported);
+        }
+        Ok(())
+    }
+
+    /// Evaluate the best 5-card hand out of 7. Jokers can only be ranked
+    /// when they are wild.
+    pub fn evaluate_seven(&self, cards: &[Card; 7]) -> Result<HandRank, DeckError> {
+        if self.wild.any() {
+            return Ok(self.evaluate_wild(cards)?.rank);
+        }
+        self.check_jokers(cards)?;
+
+        Ok(cards
+            .iter()
+            .copied()
+            .combinations(5)
+            .map(|c5| self.evaluate_five(&c5))
+            .max()
+            .unwrap())
+    }
+
+    /// Evaluate the best 5-card hand out of 7, reporting what the wild cards
+    /// were used as. Wild cards may duplicate a card already in the hand,
+    /// which is how five of a kind is made.
+    pub fn evaluate_wild(&self, cards: &[Card; 7]) -> Result<WildHand, DeckError> {
+        self.check_jokers(cards)?;
+        Ok(cards
+            .iter()
+            .copied()
+            .combinations(5)
+            .map(|c5| self.evaluate_five_wild(&c5))
+            .max_by(|a, b| a.rank.cmp(&b.rank))
+            .unwrap())
+    }
+
+    /// Jokers have no rank of their own, so they are only allowed when wild.
+    fn check_jokers(&self, cards: &[Card]) -> Result<(), DeckError> {
+        if !self.wild.jokers && cards.iter().any(|c| c.is_joker()) {
+            return Err(DeckError::JokersNotSupported);
+        }
+        Ok(())
+    }
+
+    fn is_wild(&self, card: &Card) -> bool {
+        (self.wild.jokers && card.is_joker()) || self.wild.rank == Some(card.rank)
+    }
+
+    /// Bug jokers only count as Aces, except to fill straights and flushes.
+    fn bug_allows(&self, joker_subs: &[Card], category: HandCategory) -> bool {
+        !self.wild.bug
+            || matches!(category, HandCategory::Straight | HandCategory::Flush | HandCategory::StraightFlush)
+            || joker_subs.iter().all(|c| c.rank == Rank::Ace)
+    }
+
+    /// Try every useful substitution for the wild cards among 5 cards.
+    fn evaluate_five_wild(&self, cards: &[Card]) -> WildHand {
+        let (mut wilds, naturals): (Vec<Card>, Vec<Card>) = cards.iter().partition(|c| self.is_wild(c));
+        if wilds.is_empty() {
+            return WildHand { rank: self.evaluate_five(cards), substitutions: Vec::new() };
+        }
+
+        // suits only matter for flushes, and a flush can only be in a suit
+        // already held (any one suit will do when nothing natural is held)
+        let mut suits: Vec<Suit> = naturals.iter().map(|c| c.suit).unique().collect();
+        if suits.is_empty() {
+            suits.push(Suit::Spades);
+        }
+        let candidates: Vec<Card> = self
+            .kind
+            .ranks()
+            .flat_map(|rank| suits.iter().map(move |&suit| Card { rank, suit }))
+            .collect();
+
+        // jokers get their own substitutions so the bug rule can check each one
+        wilds.sort_by_key(|c| !c.is_joker());
+        let jokers = wilds.iter().filter(|c| c.is_joker()).count();
+        let joker_subs = candidates.iter().copied().combinations_with_replacement(jokers);
+        let mut best: Option<(HandRank, Vec<Card>)> = None;
+        for (joker_subs, other_subs) in
+            joker_subs.cartesian_product(candidates.iter().copied().combinations_with_replacement(wilds.len() - jokers))
+        {
+            let mut hand = naturals.clone();
+            hand.extend(&joker_subs);
+            hand.extend(&other_subs);
+            let rank = self.evaluate_five(&hand);
+            if !self.bug_allows(&joker_subs, rank.category) {
+                continue;
+            }
+            if best.as_ref().is_none_or(|(b, _)| rank > *b) {
+                best = Some((rank, [joker_subs, other_subs].concat()));
+            }
+        }
+        let (rank, subs) = best.expect("five of a kind is always possible");
+        WildHand { rank, substitutions: wilds.into_iter().zip(subs).collect() }
+    }
+
+    /// Category position used for ordering.
+    fn strength(&self, category: HandCategory) -> u8 {
+        match (self.kind, category) {
+            (DeckKind::Standard, c) => c as u8,
+            (_, HandCategory::Flush) => HandCategory::FullHouse as u8,
+            (_, HandCategory::FullHouse) => HandCategory::Flush as u8,
+            (_, c) => c as u8,
+        }
+    }
+
+    fn evaluate_five(&self, cards: &[Card]) -> HandRank {
+        let (category, kickers) = evaluate_five(cards, self.kind.lowest_rank());
+        HandRank { category, kickers, strength: self.strength(category) }
+    }
+}
+
+/// Core evaluator for exactly 5 cards; `lowest` is the deck's lowest rank,
+/// which decides the Ace-low straight.
+fn evaluate_five(cards: &[Card], lowest: Rank) -> (HandCategory, [Rank; 5]) {
     debug_assert_eq!(cards.len(), 5);
 
     // ----- tally ranks & suits -----
@@ -60,9 +245,9 @@ fn evaluate_five(cards: &[Card]) -> HandRank {
         suit_counts[c.suit as usize] += 1;
     }
 
-    let is_flush = suit_counts.iter().any(|&n| n == 5);
+    let is_flush = suit_counts.contains(&5);
 
-    // ----- detect straight (incl. wheel A‑2‑3‑4‑5) -----
+    // ----- detect straight (incl. wheel A‑2‑3‑4‑5, or A-6-7-8-9 in short deck) -----
     let mut straight_high: Option<Rank> = None;
     for hi in (5..=14).rev() {
         if (0..5).all(|i| rank_counts[hi - i] > 0) {
@@ -71,10 +256,11 @@ fn evaluate_five(cards: &[Card]) -> HandRank {
         }
     }
     // wheel check
+    let low = lowest as usize;
     if straight_high.is_none()
-        && (rank_counts[14] > 0 && (1..=4).all(|r| rank_counts[r + 1] > 0))
+        && (rank_counts[14] > 0 && (low..low + 4).all(|r| rank_counts[r] > 0))
     {
-        straight_high = Some(Rank::Five);
+        straight_high = Some(num_to_rank(low + 3));
     }
 
     // ----- grouped ranks -----
@@ -88,77 +274,120 @@ fn evaluate_five(cards: &[Card]) -> HandRank {
     // helpers
     let take_kickers = |g: &[(u8, Rank)]| -> Vec<Rank> {
         g.iter()
-            .flat_map(|&(cnt, r)| std::iter::repeat(r).take(cnt as usize))
+            .flat_map(|&(cnt, r)| std::iter::repeat_n(r, cnt as usize))
             .collect()
     };
 
     // ----- classify -----
-    let rank = if is_flush && straight_high.is_some() {
+    if groups[0].0 == 5 {
+        (HandCategory::FiveOfAKind, [groups[0].1, Rank::Two, Rank::Two, Rank::Two, Rank::Two])
+    } else if let (true, Some(hi)) = (is_flush, straight_high) {
         // straight flush
-        HandRank {
-            category: HandCategory::StraightFlush,
-            kickers: [straight_high.unwrap(), Rank::Two, Rank::Two, Rank::Two, Rank::Two],
-        }
+        (HandCategory::StraightFlush, [hi, Rank::Two, Rank::Two, Rank::Two, Rank::Two])
     } else if groups[0].0 == 4 {
         // quads
         let kickers = take_kickers(&groups);
-        HandRank {
-            category: HandCategory::FourOfAKind,
-            kickers: [kickers[0], kickers[1], Rank::Two, Rank::Two, Rank::Two],
-        }
+        (HandCategory::FourOfAKind, [kickers[0], kickers[1], Rank::Two, Rank::Two, Rank::Two])
     } else if groups[0].0 == 3 && groups[1].0 == 2 {
-        HandRank {
-            category: HandCategory::FullHouse,
-            kickers: [groups[0].1, groups[1].1, Rank::Two, Rank::Two, Rank::Two],
-        }
+        (HandCategory::FullHouse, [groups[0].1, groups[1].1, Rank::Two, Rank::Two, Rank::Two])
     } else if is_flush {
         let mut ranks = cards
             .iter()
             .map(|c| c.rank)
             .collect::<Vec<_>>();
         ranks.sort_by(|a, b| b.cmp(a));
-        HandRank {
-            category: HandCategory::Flush,
-            kickers: [ranks[0], ranks[1], ranks[2], ranks[3], ranks[4]],
-        }
+        (HandCategory::Flush, [ranks[0], ranks[1], ranks[2], ranks[3], ranks[4]])
     } else if let Some(hi) = straight_high {
-        HandRank {
-            category: HandCategory::Straight,
-            kickers: [hi, Rank::Two, Rank::Two, Rank::Two, Rank::Two],
-        }
+        (HandCategory::Straight, [hi, Rank::Two, Rank::Two, Rank::Two, Rank::Two])
     } else if groups[0].0 == 3 {
         let kickers = take_kickers(&groups);
-        HandRank {
-            category: HandCategory::ThreeOfAKind,
-            kickers: [kickers[0], kickers[1], kickers[2], kickers[3], kickers[4]],
-        }
+        (HandCategory::ThreeOfAKind, [kickers[0], kickers[1], kickers[2], kickers[3], kickers[4]])
     } else if groups[0].0 == 2 && groups[1].0 == 2 {
         let kickers = take_kickers(&groups);
-        HandRank {
-            category: HandCategory::TwoPair,
-            kickers: [kickers[0], kickers[1], kickers[2], kickers[3], Rank::Two],
-        }
+        (HandCategory::TwoPair, [kickers[0], kickers[1], kickers[2], kickers[3], Rank::Two])
     } else if groups[0].0 == 2 {
         let kickers = take_kickers(&groups);
-        HandRank {
-            category: HandCategory::OnePair,
-            kickers: [kickers[0], kickers[1], kickers[2], kickers[3], kickers[4]],
-        }
+        (HandCategory::OnePair, [kickers[0], kickers[1], kickers[2], kickers[3], kickers[4]])
     } else {
         // high card
         let mut ks = cards.iter().map(|c| c.rank).collect::<Vec<_>>();
         ks.sort_by(|a, b| b.cmp(a));
-        HandRank {
-            category: HandCategory::HighCard,
-            kickers: [ks[0], ks[1], ks[2], ks[3], ks[4]],
-        }
-    };
-
-    rank
+        (HandCategory::HighCard, [ks[0], ks[1], ks[2], ks[3], ks[4]])
+    }
 }
 
 /// helper: 2-14 -> Rank
 #[inline]
 fn num_to_rank(n: usize) -> Rank {
     unsafe { std::mem::transmute::<u8, Rank>(n as u8) }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit;
+
+    fn hand(cards: [(Rank, Suit); 7]) -> [Card; 7] {
+        cards.map(|(rank, suit)| Card { rank, suit })
+    }
+
+    #[test]
+    fn short_deck_wheel_and_flush_over_full_house() {
+        use Rank::*;
+        use Suit::*;
+        let short = Evaluator::new(DeckKind::ShortDeck);
+
+        let wheel = hand([(Ace, Clubs), (Six, Hearts), (Seven, Spades), (Eight, Clubs), (Nine, Diamonds), (King, Hearts), (King, Spades)]);
+        assert_eq!(short.evaluate_seven(&wheel).unwrap().category, HandCategory::Straight);
+        assert_eq!(evaluate_seven(&wheel).unwrap().category, HandCategory::OnePair);
+
+        let flush = hand([(Ace, Hearts), (Six, Hearts), (Eight, Hearts), (Ten, Hearts), (Queen, Hearts), (Seven, Clubs), (Nine, Spades)]);
+        let boat = hand([(Ace, Hearts), (Ace, Clubs), (Ace, Spades), (King, Hearts), (King, Clubs), (Seven, Clubs), (Nine, Spades)]);
+        assert!(short.evaluate_seven(&flush).unwrap() > short.evaluate_seven(&boat).unwrap());
+        assert!(evaluate_seven(&flush).unwrap() < evaluate_seven(&boat).unwrap());
+    }
+
+    #[test]
+    fn wild_cards_make_five_of_a_kind_and_respect_the_bug() {
+        use Rank::*;
+        use Suit::*;
+        let deuces = Evaluator::default().with_wild(WildCards::rank(Two));
+        let quads = hand([(Ace, Clubs), (Ace, Hearts), (Ace, Spades), (Two, Clubs), (Two, Hearts), (Seven, Clubs), (Nine, Spades)]);
+        let best = deuces.evaluate_wild(&quads).unwrap();
+        assert_eq!(best.rank.category, HandCategory::FiveOfAKind);
+        assert_eq!(best.substitutions.len(), 2);
+        assert!(best.substitutions.iter().all(|(_, sub)| sub.rank == Ace));
+
+        let bug = Evaluator::default().with_wild(WildCards { bug: true, ..WildCards::jokers() });
+        let kings = hand([(King, Clubs), (King, Hearts), (Queen, Spades), (Eight, Clubs), (Three, Diamonds), (Four, Hearts), (Jack, Spades)]);
+        let with_joker = [Card::RED_JOKER, kings[0], kings[1], kings[2], kings[3], kings[4], kings[5]];
+        let best = bug.evaluate_wild(&with_joker).unwrap();
+        assert_eq!(best.rank.category, HandCategory::OnePair);
+        assert_eq!(best.substitutions, vec![(Card::RED_JOKER, Card { rank: Ace, suit: Clubs })]);
+
+        // with deuces wild too, the deuce can be the fourth King but the bug
+        // still has to be an Ace
+        let both = Evaluator::default().with_wild(WildCards { jokers: true, rank: Some(Two), bug: true });
+        let trips = hand([(King, Clubs), (King, Hearts), (King, Spades), (Eight, Clubs), (Three, Diamonds), (Two, Spades), (Four, Hearts)]);
+        let with_joker = [Card::BLACK_JOKER, trips[0], trips[1], trips[2], trips[3], trips[4], trips[5]];
+        let best = both.evaluate_wild(&with_joker).unwrap();
+        assert_eq!(best.rank.category, HandCategory::FourOfAKind);
+        for (wild, sub) in best.substitutions {
+            assert_eq!(sub.rank, if wild.is_joker() { Ace } else { King });
+        }
+    }
+
+    #[test]
+    fn jokers_are_an_error_unless_wild() {
+        use Rank::*;
+        use Suit::*;
+        let cards = hand([(Ace, Clubs), (Ace, Hearts), (Ace, Spades), (Two, Clubs), (Two, Hearts), (Seven, Clubs), (Nine, Spades)]);
+        let mut with_joker = cards;
+        with_joker[6] = Card::BLACK_JOKER;
+
+        assert_eq!(evaluate_seven(&with_joker), Err(DeckError::JokersNotSupported));
+        let deuces = Evaluator::default().with_wild(WildCards::rank(Two));
+        assert!(deuces.evaluate_wild(&with_joker).is_err());
+        let jokers = Evaluator::default().with_wild(WildCards::jokers());
+        assert_eq!(jokers.evaluate_seven(&with_joker).unwrap().category, HandCategory::FourOfAKind);
+    }
+}
@@ -0,0 +1,247 @@
+//! fair.rs - provably fair shuffling with a commit/reveal scheme
+//!
+//! The server picks a secret seed and publishes its SHA-256 hash (the
+//! commitment) before the hand. Every player then adds a client seed. The deck
+//! order is derived from all of the seeds together, so neither side can pick
+//! it alone. After the hand the server seed is revealed and anyone can re-run
+//! the shuffle with `verify_shuffle`.
+
+use crate::{Card, Deck, DeckConfig};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// SHA-256 of the server seed, published before any cards are dealt.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Commitment(pub [u8; 32]);
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything needed to reproduce a shuffle once the hand is over.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reveal {
+    pub server_seed: [u8; 32],
+    pub client_seeds: Vec<Vec<u8>>,
+    pub config: DeckConfig,
+}
+
+impl Reveal {
+    /// The full deck in dealing order (first card dealt first).
+    pub fn deal_order(&self) -> Vec<Card> {
+        let mut cards = shuffled(&self.server_seed, &self.client_seeds, &self.config);
+        cards.reverse();
+        cards
+    }
+}
+
+/// Server-side state for one hand's shuffle.
+pub struct FairShuffle {
+    server_seed: [u8; 32],
+    client_seeds: Vec<Vec<u8>>,
+    config: DeckConfig,
+}
+
+impl FairShuffle {
+    pub fn new(server_seed: [u8; 32]) -> Self {
+        Self { server_seed, client_seeds: Vec::new(), config: DeckConfig::default() }
+    }
+
+    /// Shuffle this deck composition instead of a standard deck.
+    pub fn with_config(mut self, config: DeckConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Start a shuffle with a fresh random server seed.
+    pub fn random() -> Self {
+        Self::new(rand::thread_rng().r#gen())
+    }
+
+    /// The hash to publish before the hand starts.
+    pub fn commitment(&self) -> Commitment {
+        Commitment(Sha256::digest(self.server_seed).into())
+    }
+
+    /// Mix in one player's seed. Order matters, so add them in seat order.
+    pub fn add_client_seed(&mut self, seed: impl AsRef<[u8]>) {
+        self.client_seeds.push(seed.as_ref().to_vec());
+    }
+
+    /// The deck to deal this hand from.
+    pub fn deck(&self) -> Deck {
+        Deck { cards: shuffled(&self.server_seed, &self.client_seeds, &self.config) }
+    }
+
+    /// Hand out the seeds after the hand so players can check the shuffle.
+    pub fn reveal(self) -> Reveal {
+        Reveal {
+            server_seed: self.server_seed,
+            client_seeds: self.client_seeds,
+            config: self.config,
+        }
+    }
+}
+
+/// Why a shuffle failed verification.
+#[derive(Debug, Eq, PartialEq)]
+pub enum FairnessError {
+    /// The revealed server seed does not hash to the published commitment.
+    CommitmentMismatch,
+    /// The seeds produce a different card at `position` than was dealt.
+    CardMismatch { position: usize, expected: Card, dealt: Card },
+    /// None of the dealt cards were known, so there was nothing to check.
+    NoKnownCards,
+    /// More cards were dealt than the deck holds.
+    TooManyCards { dealt: usize, deck: usize },
+}
+
+impl fmt::Display for FairnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FairnessError::CommitmentMismatch => {
+                write!(f, "server seed does not match the commitment")
+            }
+            FairnessError::CardMismatch { position, expected, dealt } => write!(
+                f,
+                "card {} should have been {} but {} was dealt",
+                position, expected, dealt
+            ),
+            FairnessError::NoKnownCards => write!(f, "no known cards to check"),
+            FairnessError::TooManyCards { dealt, deck } => {
+                write!(f, "{} cards dealt from a deck of {}", dealt, deck)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FairnessError {}
+
+/// Check a finished hand: the revealed seed must match `commitment`, and the
+/// cards a player saw must be where the seeds put them. `dealt` lists every
+/// card that came off the deck in order, burns included, with `None` for
+/// the ones they didn't see (burns, other players' hole cards).
+pub fn verify_shuffle(commitment: &Commitment, reveal: &Reveal, dealt: &[Option<Card>]) -> Result<(), FairnessError> {
+    let hash: [u8; 32] = Sha256::digest(reveal.server_seed).into();
+    if hash != commitment.0 {
+        return Err(FairnessError::CommitmentMismatch);
+    }
+
+    let order = reveal.deal_order();
+    if dealt.iter().all(Option::is_none) {
+        return Err(FairnessError::NoKnownCards);
+    }
+    if dealt.len() > order.len() {
+        return Err(FairnessError::TooManyCards { dealt: dealt.len(), deck: order.len() });
+    }
+
+    for (position, (&expected, &dealt)) in order.iter().zip(dealt).enumerate() {
+        let Some(dealt) = dealt else { continue };
+        if expected != dealt {
+            return Err(FairnessError::CardMismatch { position, expected, dealt });
+        }
+    }
+    Ok(())
+}
+
+/// Deck order (in `Deck` storage order, top card last) for a set of seeds.
+///
+/// Spelled out so anyone can reproduce it without this crate: the ChaCha20
+/// key is SHA-256 of the server seed followed by each client seed, each
+/// prefixed with its length as a little-endian u64. Starting from
+/// `DeckConfig::cards`, for `i` from the last index down to 1, swap card `i`
+/// with card `j`, where `j` is the next 64-bit keystream word (two 32-bit
+/// words, little-endian) modulo `i + 1`. Words at or above the largest
+/// multiple of `i + 1` are skipped so every `j` is equally likely.
+fn shuffled(server_seed: &[u8; 32], client_seeds: &[Vec<u8>], config: &DeckConfig) -> Vec<Card> {
+    // length-prefix each client seed so ["ab", "c"] and ["a", "bc"] differ
+    let mut hasher = Sha256::new();
+    hasher.update(server_seed);
+    for seed in client_seeds {
+        hasher.update((seed.len() as u64).to_le_bytes());
+        hasher.update(seed);
+    }
+
+    let mut rng = ChaCha20Rng::from_seed(hasher.finalize().into());
+    let mut cards = config.cards();
+    for i in (1..cards.len()).rev() {
+        let bound = i as u64 + 1;
+        let limit = u64::MAX / bound * bound;
+        let j = loop {
+            let word = rng.next_u64();
+            if word < limit {
+                break (word % bound) as usize;
+            }
+        };
+        cards.swap(i, j);
+    }
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revealed_shuffle_verifies() {
+        let mut shuffle = FairShuffle::new([7; 32]);
+        shuffle.add_client_seed("alice");
+        shuffle.add_client_seed("bob");
+        let commitment = shuffle.commitment();
+
+        // heads-up, seat 0 sees its own hole cards (dealt 1st and 3rd) and
+        // the board, but not the burns or seat 1's cards
+        let mut deck = shuffle.deck();
+        let seen = [true, false, true, false, false, true, true, true, false, true, false, true];
+        let dealt: Vec<Option<Card>> = seen.iter().map(|&seen| deck.deal().filter(|_| seen)).collect();
+
+        let reveal = shuffle.reveal();
+        assert_eq!(verify_shuffle(&commitment, &reveal, &dealt), Ok(()));
+
+        let mut tampered = reveal.clone();
+        tampered.server_seed[0] ^= 1;
+        assert_eq!(verify_shuffle(&commitment, &tampered, &dealt), Err(FairnessError::CommitmentMismatch));
+
+        assert_eq!(verify_shuffle(&commitment, &reveal, &[None; 5]), Err(FairnessError::NoKnownCards));
+        let too_many = vec![dealt[0]; 53];
+        assert_eq!(
+            verify_shuffle(&commitment, &reveal, &too_many),
+            Err(FairnessError::TooManyCards { dealt: 53, deck: 52 })
+        );
+
+        let mut swapped = dealt.clone();
+        swapped.swap(0, 2);
+        assert!(matches!(
+            verify_shuffle(&commitment, &reveal, &swapped),
+            Err(FairnessError::CardMismatch { position: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn shuffle_matches_known_answers() {
+        use crate::{Rank, Suit};
+        let card = |rank, suit| Card { rank, suit };
+
+        // pinned so the published algorithm can't drift between releases
+        let mut shuffle = FairShuffle::new([0; 32]);
+        shuffle.add_client_seed("alice");
+        let first: Vec<Card> = shuffle.reveal().deal_order().into_iter().take(5).collect();
+        assert_eq!(
+            first,
+            vec![
+                card(Rank::Five, Suit::Spades),
+                card(Rank::Nine, Suit::Diamonds),
+                card(Rank::Queen, Suit::Spades),
+                card(Rank::Three, Suit::Hearts),
+                card(Rank::Ace, Suit::Spades),
+            ]
+        );
+    }
+}
@@ -1,6 +1,6 @@
-//! game.rs - hand flow & street progression (no betting yet)
+//! game.rs - hand flow: forced bets, betting each street, runouts and showdown
 
-use crate::{Action, BetRound, Deck, Table, Card}; 
+use crate::{Action, ButtonRule, Chips, Denomination, HandEvent, ShownHand, Positions, ActionError, ActionOutcome, BetRound, BetStructure, Card, Deck, DeckConfig, DeckError, Evaluator, HandResult, LegalActions, OddChipRule, Pot, RakePolicy, RaiseRules, ShowdownChoice, SidePot, Stakes, Table}; 
 
 /// The five phases of a Hold'em hand. 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -12,6 +12,106 @@ pub enum Street {
     Showdown,
 } 
 
+/// Known cards for setting up a specific hand. Anything not given here is
+/// dealt at random from the cards that are left.
+#[derive(Clone, Debug, Default)]
+pub struct Scenario {
+    hole_cards: Vec<(usize, [Card; 2])>,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give the player at `seat_idx` these two hole cards.
+    pub fn hole_cards(mut self, seat_idx: usize, cards: [Card; 2]) -> Self {
+        self.hole_cards.retain(|(i, _)| *i != seat_idx);
+        self.hole_cards.push((seat_idx, cards));
+        self
+    }
+
+    /// Fix the board from the flop onwards (up to five cards, in dealing
+    /// order; more is an error when the deck is built).
+    pub fn board(mut self, cards: &[Card]) -> Self {
+        self.board = cards.to_vec();
+        self
+    }
+
+    /// Take a card out of play, e.g. one known to be mucked.
+    pub fn dead(mut self, card: Card) -> Self {
+        self.dead.push(card);
+        self
+    }
+
+    /// Build the deck that deals this scenario at `table`, following the same
+    /// order as `Table::deal_hole_cards` and the burns in `deal_next_street`.
+    /// Fails if a card is used twice, the board is too long, or hole cards
+    /// are given for a seat that isn't dealt in.
+    pub fn deck_for(&self, table: &Table, config: DeckConfig) -> Result<Deck, DeckError> {
+        if self.board.len() > 5 {
+            return Err(DeckError::TooManyBoardCards(self.board.len()));
+        }
+        let seats = table.deal_order();
+        if let Some(&(seat, _)) = self.hole_cards.iter().find(|(i, _)| !seats.contains(i)) {
+            return Err(DeckError::SeatNotDealtIn(seat));
+        }
+
+        let mut order = Vec::new();
+        for round in 0..2 {
+            for seat in &seats {
+                let known = self.hole_cards.iter().find(|(i, _)| i == seat);
+                order.push(known.map(|(_, cards)| cards[round]));
+            }
+        }
+
+        // burn + flop, burn + turn, burn + river
+        let board = |i: usize| self.board.get(i).copied();
+        order.extend([None, board(0), board(1), board(2)]);
+        order.extend([None, board(3)]);
+        order.extend([None, board(4)]);
+
+        let mut builder = Deck::builder().config(config).stack(order);
+        for &card in &self.dead {
+            builder = builder.remove(card);
+        }
+        builder.build()
+    }
+}
+
+/// Antes posted before the cards are dealt. They are dead money: they go in
+/// the pot but don't count towards anyone's bet. Amounts can be combined.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Antes {
+    /// Posted by every player dealt in.
+    pub each: Chips,
+    /// Posted once by the big blind on behalf of the table.
+    pub big_blind: Chips,
+    /// Posted once by the button, as in short deck.
+    pub button: Chips,
+}
+
+/// Where the first straddle may come from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StraddleKind {
+    /// The player under the gun, left of the big blind.
+    Utg,
+    /// The button (the "Mississippi" straddle).
+    Button,
+}
+
+/// Which voluntary straddles are allowed. Each straddle is twice the big
+/// blind or the straddle before it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct StraddleRules {
+    /// Who may make the first straddle; `None` turns straddling off.
+    pub first: Option<StraddleKind>,
+    /// How many re-straddles may follow, each by the next player to the left.
+    pub max_restraddles: u8,
+}
+
 /// A running hand of poker. 
 pub struct GameState {
     pub table: Table, 
@@ -19,128 +119,951 @@ pub struct GameState {
     pub street: Street, 
     pub board: Vec<Card>, 
     pub bet_round: Option<BetRound>,
-    pub pot: u32, 
+    /// Chips from betting rounds that are over; see `current_pot` for
+    /// everything including bets still in front of the players.
+    pub pot: Pot, 
 
     /// Seat index of the player whose turn is to act 
     pub to_act: usize, 
 
-    /// Size of blinds (SB = small blind, BB = 2xSB). 
-    pub small_blind: u32
+    /// Size of blinds (SB = small blind, BB = big blind, 2xSB unless changed). 
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    /// What a chip is worth, for showing amounts as money.
+    pub denomination: Denomination,
+    /// Antes posted before each hand.
+    pub antes: Antes,
+    /// Which straddles are allowed.
+    pub straddle_rules: StraddleRules,
+    /// Seats that want to straddle next hand.
+    straddle_requests: Vec<usize>,
+    /// Last seat to bet or raise on the current street; shows first.
+    last_aggressor: Option<usize>,
+    /// Show or muck decisions made for this hand's showdown.
+    showdown_choices: Vec<(usize, ShowdownChoice)>,
+
+    /// Everything that happened in the current hand.
+    pub history: Vec<HandEvent>,
+    /// Who won what, once the hand is over.
+    pub result: Option<HandResult>,
+    /// Who gets the odd chips of a split pot.
+    pub odd_chips: OddChipRule,
+    /// The house's cut; none by default.
+    pub rake: RakePolicy,
+    /// How many times the board is run out when betting ends early because
+    /// players are all-in (see `run_it`).
+    pub runouts: u8,
+    /// Moving or dead button.
+    pub button_rule: ButtonRule,
+    /// Button and blinds of the current (or last) hand.
+    positions: Option<Positions>,
+
+    /// Composition of the deck shuffled for each hand.
+    deck_config: DeckConfig,
+    /// Hand rankings matching `deck_config`; only set through
+    /// `configure_deck` so the two always agree.
+    evaluator: Evaluator,
+    /// How short all-in raises are handled.
+    pub raise_rules: RaiseRules,
+    /// No-limit, pot-limit or fixed-limit bet sizing.
+    pub bet_structure: BetStructure,
 }
 
 impl GameState {
     /// Create a fresh game around an existing `Table`
-    pub fn new(table: Table, small_blind: u32) -> Self {
+    pub fn new(table: Table, small_blind: impl Into<Chips>) -> Self {
+        let small_blind = small_blind.into(); 
         Self {
             table, 
             deck: Deck::new_shuffled(),
             street: Street::Preflop, // nothing running yet 
             board: Vec::with_capacity(5), 
-            pot: 0, 
+            pot: Pot::default(), 
             bet_round: None, 
             to_act: 0,
             small_blind, 
+            big_blind: small_blind * 2,
+            denomination: Denomination::default(),
+            antes: Antes::default(),
+            straddle_rules: StraddleRules::default(),
+            straddle_requests: Vec::new(),
+            last_aggressor: None,
+            showdown_choices: Vec::new(),
+            history: Vec::new(),
+            result: None,
+            odd_chips: OddChipRule::default(),
+            rake: RakePolicy::default(),
+            runouts: 1,
+            button_rule: ButtonRule::default(),
+            positions: None,
+            deck_config: DeckConfig::default(),
+            evaluator: Evaluator::default(),
+            raise_rules: RaiseRules::default(),
+            bet_structure: BetStructure::default(),
+        }
+    }
+
+    /// Create a game at `stakes`, e.g. 0.25/0.50 played in cent chips.
+    pub fn with_stakes(table: Table, stakes: Stakes) -> Self {
+        let mut game = Self::new(table, stakes.small_blind);
+        game.big_blind = stakes.big_blind;
+        game.denomination = stakes.denomination;
+        game
+    }
+
+    /// The blinds and what the chips are worth.
+    pub fn stakes(&self) -> Stakes {
+        Stakes { small_blind: self.small_blind, big_blind: self.big_blind, denomination: self.denomination.clone() }
+    }
+
+    /// Play with a different deck, e.g. short deck. The evaluator must be
+    /// able to rank every hand the deck can make, and there must be enough
+    /// cards for every seat plus one board and its burns. Extra runouts are
+    /// capped to what is left (see `run_it`).
+    pub fn configure_deck(&mut self, config: DeckConfig, evaluator: Evaluator) -> Result<(), DeckError> {
+        evaluator.check_deck(&config)?;
+
+        let needed = 2 * self.table.seat_count() + 8; 
+        if config.size() < needed {
+            return Err(DeckError::NotEnoughCards { needed, available: config.size() });
+        }
+
+        self.deck_config = config;
+        self.evaluator = evaluator;
+        Ok(())
+    }
+
+    pub fn deck_config(&self) -> &DeckConfig {
+        &self.deck_config
+    }
+
+    pub fn evaluator(&self) -> &Evaluator {
+        &self.evaluator
+    }
+
+    /// Button and blinds of the current hand, once one has started.
+    pub fn positions(&self) -> Option<Positions> {
+        self.positions
+    }
+
+    /// Ask to straddle next hand. It is only posted if the seat ends up in a
+    /// straddling position under `straddle_rules` and can cover it.
+    pub fn volunteer_straddle(&mut self, seat_idx: usize) {
+        if !self.straddle_requests.contains(&seat_idx) {
+            self.straddle_requests.push(seat_idx); 
         }
     }
 
     pub fn start_hand(&mut self) {
-        // advance to the next player
-        self.table.advance_button();
+        self.deal_hand(Deck::with_config(&self.deck_config)); 
+    }
+
+    /// Start a hand that deals the cards fixed by `scenario`; the rest are
+    /// random. Nothing changes if the scenario can't be dealt.
+    pub fn start_scenario(&mut self, scenario: &Scenario) -> Result<(), DeckError> {
+        let deck = scenario.deck_for(&self.table, self.deck_config)?; 
+        self.deal_hand(deck); 
+        Ok(())
+    }
+
+    /// Start a hand dealing from `deck` instead of a freshly shuffled one.
+    /// The deck must hold enough cards for every seat and a board, and no
+    /// jokers unless the evaluator plays them wild; otherwise nothing changes.
+    pub fn start_hand_with_deck(&mut self, deck: Deck) -> Result<(), DeckError> {
+        let needed = 2 * self.table.seat_count() + 8; 
+        if deck.remaining() < needed {
+            return Err(DeckError::NotEnoughCards { needed, available: deck.remaining() });
+        }
+        if !self.evaluator.wild().jokers && deck.cards.iter().any(Card::is_joker) {
+            return Err(DeckError::JokersNotSupported);
+        }
+        self.deal_hand(deck); 
+        Ok(())
+    }
+
+    /// Start a hand from a deck already known to be playable.
+    fn deal_hand(&mut self, deck: Deck) {
+        // move the button and blinds on
+        let positions = Positions::next(&mut self.table, self.button_rule, self.positions); 
+        self.positions = Some(positions); 
 
+        self.deck = deck; 
         self.board.clear(); 
-        self.pot = 0; 
+        self.pot = Pot::new(self.table.seat_count()); 
+        self.history.clear(); 
+        self.result = None; 
+        self.showdown_choices.clear(); 
+        self.history.push(HandEvent::HandStarted { button: self.table.dealer_button }); 
 
         // reset players
-        for seat in self.table.seats_mut() {
-            if let Some(p) = seat {
-                p.clear_hand(); 
-            }
+        for p in self.table.seats_mut().iter_mut().flatten() {
+            p.clear_hand(); 
         }
 
-        self.table.deal_hole_cards(&mut self.deck); 
+        // forced bets and straddles go in before the cards are dealt
+        self.street = Street::Preflop; 
+        self.new_bet_round(); 
+        let straddlers = self.post_forced_bets(positions); 
+        let bb_idx = positions.big_blind; 
 
-        // the small blind is put by the player to the left of the dealer button
-        let sb_idx = self.table.next_occupied(self.table.dealer_button); 
-        let bb_idx = self.table.next_occupied(sb_idx);  
+        self.table.deal_hole_cards(&mut self.deck); 
     
-        // set action pointer to UTG (first to act pre-flop)
-        self.to_act = self.table.next_occupied(bb_idx); 
+        // set action pointer to UTG (first to act pre-flop), left of any straddles
+        let last_forced = straddlers.last().copied().unwrap_or(bb_idx); 
+        self.to_act = self.first_to_act(last_forced); 
 
-        self.street = Street::Preflop; 
-        self.bet_round = Some(BetRound::new(&self.table, self.table.dealer_button)); 
+        // the blinds can leave nobody able to bet
+        if !self.betting_open() {
+            let _ = self.run_it(self.runouts); 
+        }
+    }
+
+    /// Take this hand's antes, blinds and straddles; short stacks post what
+    /// they have. The big blind's own blind comes before the big-blind ante.
+    /// Returns the seats that straddled.
+    fn post_forced_bets(&mut self, positions: Positions) -> Vec<usize> {
+        let bb_idx = positions.big_blind; 
+        let requests = std::mem::take(&mut self.straddle_requests); 
+        let Some(round) = &mut self.bet_round else { return Vec::new() };
+        let table = &mut self.table; 
+        let history = &mut self.history; 
+
+        if !self.antes.each.is_zero() {
+            for seat in table.deal_order() {
+                let amount = round.post_ante(table, seat, self.antes.each); 
+                history.push(HandEvent::Ante { seat, amount }); 
+            }
+        }
+
+        if let Some(seat) = positions.small_blind {
+            let amount = round.post_blind(table, seat, self.small_blind); 
+            history.push(HandEvent::Blind { seat, amount }); 
+            if let Some(p) = table.seat_mut(seat) {
+                p.missed_small_blind = false; 
+            }
+        }
+        let amount = round.post_big_blind(table, bb_idx, self.big_blind); 
+        history.push(HandEvent::Blind { seat: bb_idx, amount }); 
+        if let Some(p) = table.seat_mut(bb_idx) {
+            p.missed_small_blind = false; 
+            p.missed_big_blind = false; 
+        }
+
+        // players back from sitting out make up what they missed: the big
+        // blind as a live bet (topping up a small blind they're in), the
+        // small blind as dead money. They can't straddle on top of it.
+        let mut owed_blinds = Vec::new(); 
+        for seat in table.deal_order() {
+            let Some(p) = table.seat_mut(seat) else { continue };
+            let (small, big) = (p.missed_small_blind, p.missed_big_blind); 
+            p.missed_small_blind = false; 
+            p.missed_big_blind = false; 
+            if small || big {
+                owed_blinds.push(seat); 
+            }
+            if big {
+                let amount = round.post_missed_blind(table, seat, self.big_blind); 
+                history.push(HandEvent::Blind { seat, amount }); 
+            }
+            if small {
+                let amount = round.post_table_ante(table, seat, self.small_blind); 
+                history.push(HandEvent::DeadBlind { seat, amount }); 
+            }
+        }
+
+        // each straddle doubles the last, moving left until someone declines
+        let mut straddlers = Vec::new(); 
+        if let Some(kind) = self.straddle_rules.first {
+            let mut seat = match kind {
+                StraddleKind::Utg => table.next_occupied(bb_idx), 
+                StraddleKind::Button => table.dealer_button, 
+            };
+            let mut amount = self.big_blind * 2; 
+            for _ in 0..=self.straddle_rules.max_restraddles {
+                let can_cover = table.seat(seat).is_some_and(|p| p.chips >= amount); 
+                let in_blind = Some(seat) == positions.small_blind || seat == bb_idx || owed_blinds.contains(&seat); 
+                if in_blind || !requests.contains(&seat) || !can_cover {
+                    break; 
+                }
+                round.post_straddle(table, seat, amount); 
+                history.push(HandEvent::Straddle { seat, amount }); 
+                straddlers.push(seat); 
+                amount *= 2; 
+                seat = table.next_occupied(seat); 
+            }
+        }
+
+        if !self.antes.big_blind.is_zero() {
+            let amount = round.post_table_ante(table, bb_idx, self.antes.big_blind); 
+            history.push(HandEvent::Ante { seat: bb_idx, amount }); 
+        }
+        if !self.antes.button.is_zero() && table.is_dealt_in(table.dealer_button) {
+            let seat = table.dealer_button; 
+            let amount = round.post_table_ante(table, seat, self.antes.button); 
+            history.push(HandEvent::Ante { seat, amount }); 
+        }
+        straddlers
+    }
+
+    /// Every street starts with a fresh betting round.
+    fn new_bet_round(&mut self) {
+        let round = BetRound::new(&self.table, self.big_blind); 
+        let round = round
+            .with_rules(self.raise_rules)
+            .with_structure(self.bet_structure, self.street)
+            .with_prior_pot(self.pot.total()); 
+        self.bet_round = Some(round); 
+        self.last_aggressor = None; 
     }
 
-    /// Move from preflop > flop > turn > river > showdows
+    /// Move from preflop > flop > turn > river > showdown. Any betting round
+    /// still running is closed first, its bets going into the pot.
     pub fn deal_next_street(&mut self) {
+        self.close_bet_round(); 
+        let dealt_before = self.board.len(); 
         match self.street {
             Street::Preflop => {
-                // Burn 1, deal 3
-                self.deck.deal(); // at each betting round a card is discarded
-                for _ in 0..3 {
-                    self.board.push(self.deck.deal().unwrap())
-                }
-
+                deal_street(&mut self.deck, &mut self.board); 
                 self.street = Street::Flop; 
+                self.new_bet_round(); 
             }
 
             Street::Flop => {
-                self.deck.deal();
-                self.board.push(self.deck.deal().unwrap()); 
+                deal_street(&mut self.deck, &mut self.board); 
                 self.street = Street::Turn; 
+                self.new_bet_round(); 
             }
 
             Street::Turn => {
-                self.deck.deal(); 
-                self.board.push(self.deck.deal().unwrap()); 
+                deal_street(&mut self.deck, &mut self.board); 
                 self.street = Street::River; 
+                self.new_bet_round(); 
             }
 
             Street::River => {
-                self.street = Street::Showdown;
+                let board = self.board.clone(); 
+                self.finish_hand(&[board]); 
             }
 
-            Street::Showdown => {
-                self.bet_round = Some(BetRound::new(&self.table, self.table.dealer_button));
-            }
+            Street::Showdown => {}
+        }
+
+        if self.board.len() > dealt_before {
+            let cards = self.board[dealt_before..].to_vec(); 
+            self.history.push(HandEvent::Board { street: self.street, cards }); 
         }
 
         // first player to act is left of dealer except pre-flop
-        self.to_act = self.table.next_occupied(self.table.dealer_button); 
+        self.to_act = self.first_to_act(self.table.dealer_button); 
+    }
+
+    /// First seat after `from` who still has to act this round.
+    fn first_to_act(&self, from: usize) -> usize {
+        self.bet_round
+            .as_ref()
+            .and_then(|round| round.next_to_act(&self.table, from))
+            .unwrap_or_else(|| self.table.next_occupied(from))
+    }
+
+    /// Once nobody can bet any more (everyone left is all-in, bar at most one
+    /// player with nothing to call), deal the rest of the board `times` times
+    /// from the same deck, burns included. Each pot is split evenly between
+    /// the runouts, odd chips going to the earlier ones. If the deck can't
+    /// cover `times` runouts it is run as many times as it can; the result's
+    /// `boards` says how many.
+    pub fn run_it(&mut self, times: u8) -> Result<&HandResult, ActionError> {
+        if self.street == Street::Showdown {
+            return Err(ActionError::HandOver); 
+        }
+        if self.betting_open() {
+            return Err(ActionError::BettingNotOver); 
+        }
+        self.close_bet_round(); 
+
+        // each runout deals the rest of the board with a burn per street
+        let dealt = self.board.len(); 
+        let per_run = match dealt {
+            0 => 8, 
+            3 => 4, 
+            4 => 2, 
+            _ => 0, 
+        }; 
+        let times = match per_run {
+            0 => 1, // the board is complete, every run would be the same
+            n => (times.max(1) as usize).min(self.deck.remaining() / n).max(1), 
+        }; 
+        let mut boards = Vec::new(); 
+        for run in 0..times {
+            let mut board = self.board.clone(); 
+            while board.len() < 5 {
+                deal_street(&mut self.deck, &mut board); 
+            }
+            self.history.push(HandEvent::Runout { run, cards: board[dealt..].to_vec() }); 
+            boards.push(board); 
+        }
+        self.board = boards[0].clone(); 
+        self.finish_hand(&boards); 
+        Ok(self.result.as_ref().expect("hand just finished"))
+    }
+
+    /// Whether anyone can still bet: two players with chips behind, or one
+    /// who hasn't called the current bet yet.
+    fn betting_open(&self) -> bool {
+        let can_act: Vec<usize> = (0..self.table.seat_count())
+            .filter(|&i| self.table.seat(i).is_some_and(|p| !p.folded && !p.all_in))
+            .collect(); 
+        let owes = |seat: usize| {
+            self.bet_round.as_ref().is_some_and(|r| r.contributed(seat) < r.current_bet())
+        }; 
+        can_act.len() > 1 || can_act.iter().any(|&seat| owes(seat))
+    }
+
+    /// End the hand: take the rake, award the pots, to the last player
+    /// standing or by showdown on each of `boards`, and pay the winners.
+    fn finish_hand(&mut self, boards: &[Vec<Card>]) {
+        // betting streets played, counting the one the hand ended on; a
+        // board run out after that doesn't add any
+        let streets = match self.street {
+            Street::Preflop => 1, 
+            Street::Flop => 2, 
+            Street::Turn => 3, 
+            Street::River | Street::Showdown => 4, 
+        }; 
+        self.street = Street::Showdown; 
+        self.bet_round = None; 
+
+        let mut side_pots = self.pot.side_pots(&self.table); 
+        let rake = self.rake.take(&mut side_pots, &self.pot, streets, self.big_blind); 
+        if !rake.total.is_zero() {
+            self.history.push(HandEvent::Rake { amount: rake.total }); 
+        }
+
+        let runs = boards.len() as u32; 
+        let mut result = HandResult { rake, boards: boards.to_vec(), ..HandResult::default() }; 
+        for (run, board) in boards.iter().enumerate() {
+            let shares = side_pots
+                .iter()
+                .map(|p| SidePot {
+                    amount: p.amount / runs + Chips(u32::from((run as u32) < (p.amount % runs).0)),
+                    eligible: p.eligible.clone(),
+                })
+                .collect(); 
+            let mut run_result = crate::settle(shares, &mut self.table, board, &self.evaluator, self.odd_chips)
+                .expect("the deck was checked against the evaluator before dealing"); 
+            run_result.pots.iter_mut().for_each(|p| p.run = run); 
+            run_result.shown.iter_mut().for_each(|h| h.run = run); 
+            result.pots.append(&mut run_result.pots); 
+            result.shown.append(&mut run_result.shown); 
+        }
+        self.reveal(&mut result); 
+        for pot in &result.pots {
+            for &(seat, amount) in &pot.winners {
+                self.history.push(HandEvent::Won { seat, amount }); 
+            }
+        }
+        self.result = Some(result); 
+    }
+
+    /// Decide whether `seat_idx` shows or mucks at this hand's showdown if
+    /// their hand can't win. Without a decision the hand is shown, unless
+    /// the player has `auto_muck` on. The last player standing shows only
+    /// when asked to.
+    pub fn choose_at_showdown(&mut self, seat_idx: usize, choice: ShowdownChoice) {
+        self.showdown_choices.retain(|&(seat, _)| seat != seat_idx); 
+        self.showdown_choices.push((seat_idx, choice)); 
+    }
+
+    /// Live seats in the order they turn their cards over: the last player
+    /// to bet or raise on the final street first, otherwise the first player
+    /// left of the button, then clockwise.
+    pub fn showdown_order(&self) -> Vec<usize> {
+        let n = self.table.seat_count(); 
+        let live: Vec<usize> = (0..n)
+            .filter(|&i| self.table.seat(i).is_some_and(|p| !p.folded))
+            .collect(); 
+        let first = match self.last_aggressor {
+            Some(seat) if live.contains(&seat) => seat, 
+            _ => (self.table.dealer_button + 1) % n.max(1), 
+        }; 
+        (0..n).map(|k| (first + k) % n).filter(|seat| live.contains(seat)).collect()
+    }
+
+    /// Turn the ranked hands over in showdown order. A hand is shown if it
+    /// beats or ties the best hand shown so far on some board, if anyone is
+    /// all-in, or if its player chose to; otherwise it is mucked. The shows
+    /// and mucks go in the history.
+    fn reveal(&mut self, result: &mut HandResult) {
+        let ranked = std::mem::take(&mut result.shown); 
+        let choice = |seat: usize| self.showdown_choices.iter().find(|&&(s, _)| s == seat).map(|&(_, c)| c); 
+        let order = self.showdown_order(); 
+
+        if order.len() == 1 {
+            let seat = order[0]; 
+            let cards = self.table.seat(seat).and_then(|p| p.hole_cards()); 
+            if let (Some(ShowdownChoice::Show), Some(cards)) = (choice(seat), cards) {
+                self.history.push(HandEvent::Shown { seat, cards }); 
+            }
+            return; 
+        }
+
+        // everyone's cards go face up once a player is all-in
+        let all_in = order.iter().any(|&i| self.table.seat(i).is_some_and(|p| p.all_in)); 
+        let mut best = vec![None; result.boards.len()]; 
+        for &seat in &order {
+            let hands: Vec<ShownHand> = ranked.iter().filter(|h| h.seat == seat).cloned().collect(); 
+            let Some(first) = hands.first() else { continue }; 
+            let contends = hands.iter().any(|h| best[h.run].as_ref().is_none_or(|b| &h.rank >= b)); 
+            let wants_to_show = match choice(seat) {
+                Some(choice) => choice == ShowdownChoice::Show, 
+                None => !self.table.seat(seat).is_some_and(|p| p.auto_muck), 
+            }; 
+
+            if all_in || contends || wants_to_show {
+                self.history.push(HandEvent::Shown { seat, cards: first.cards }); 
+                for h in &hands {
+                    if best[h.run].as_ref().is_none_or(|b| &h.rank > b) {
+                        best[h.run] = Some(h.rank.clone()); 
+                    }
+                }
+                result.shown.extend(hands); 
+            } else {
+                self.history.push(HandEvent::Mucked { seat }); 
+                result.mucked.push(seat); 
+            }
+        }
     }
 
-    /// Helper: pull a blind from a player into the pot (no side-pot handling)
-    pub fn collect_blind(&mut self, seat_idx: usize, amount: u32) {
-        if let Some(player) = self.table.seat_mut(seat_idx) {
+    /// Helper: post an extra blind for a player as a live bet in the current
+    /// round (e.g. a player coming back who owes a blind). Returns what was posted.
+    pub fn collect_blind(&mut self, seat_idx: usize, amount: Chips) -> Chips {
+        match &mut self.bet_round {
             // if you can't call the full amount you put what you got
-            let contrib = amount.min(player.chips);  
-            player.chips -= contrib; 
-            self.pot += contrib; 
+            Some(round) => round.post_blind(&mut self.table, seat_idx, amount), 
+            None => Chips::ZERO, 
         }
      }
     
-    /// Advance action pointer to next active seat
+    /// End the betting round in progress, if any: hand back what nobody
+    /// called and move the rest into the pot.
+    fn close_bet_round(&mut self) {
+        if let Some(mut round) = self.bet_round.take() {
+            if let Some((seat, amount)) = round.return_uncalled(&mut self.table) {
+                self.history.push(HandEvent::UncalledBet { seat, amount }); 
+            }
+            round.collect(&mut self.pot); 
+        }
+    }
+
+    /// The pot with the current street's bets pulled in, e.g. to show the
+    /// main and side pots mid-hand.
+    pub fn current_pot(&self) -> Pot {
+        let mut pot = self.pot.clone(); 
+        if let Some(round) = &self.bet_round {
+            round.collect(&mut pot); 
+        }
+        pot
+    }
+
+    /// Advance action pointer to the next seat that still has to act
     pub fn advance_action(&mut self) {
-        self.to_act = self.table.next_occupied(self.to_act); 
+        self.to_act = self.first_to_act(self.to_act); 
     } 
 
-    pub fn player_action(&mut self, seat_idx: usize, action: Action) {
-        if let Some(round) = &mut self.bet_round {
-            let next = round.act(&mut self.table, seat_idx, action);    
-            match next {
-                Some(idx) => self.to_act = idx, 
-                None => {
-                    // betting round finished: move chips to pot and maybe next street
-                    let sidepots = round.into_sidepots(&self.table); 
-                    self.pot += sidepots.iter().map(|(p, _)| *p).sum::<u32>(); 
-
-                    if self.street != Street::River {
-                        self.deal_next_street();
-                    } else {
-                        self.street = Street::Showdown; 
-                    }
+    /// What `seat_idx` may do right now: nothing unless it's their turn in a
+    /// betting round.
+    pub fn legal_actions(&self, seat_idx: usize) -> LegalActions {
+        match &self.bet_round {
+            Some(round) if self.street != Street::Showdown && seat_idx == self.to_act => {
+                round.legal_actions(&self.table, seat_idx)
+            }
+            _ => LegalActions::default(),
+        }
+    }
+
+    /// Apply `action` for `seat_idx`, who must be the seat to act. Invalid
+    /// actions are rejected and leave the game unchanged.
+    pub fn player_action(&mut self, seat_idx: usize, action: Action) -> Result<ActionOutcome, ActionError> {
+        let round = match &mut self.bet_round {
+            Some(round) if self.street != Street::Showdown => round, 
+            _ => return Err(ActionError::HandOver), 
+        };
+        if seat_idx != self.to_act {
+            return Err(ActionError::NotYourTurn { to_act: self.to_act }); 
+        }
+
+        let bet_before = round.current_bet(); 
+        let outcome = round.act(&mut self.table, seat_idx, action)?;    
+        self.history.push(HandEvent::Action { seat: seat_idx, action }); 
+        if round.current_bet() > bet_before {
+            self.last_aggressor = Some(seat_idx); 
+        }
+        match outcome {
+            ActionOutcome::NextToAct(idx) => self.to_act = idx, 
+            ActionOutcome::RoundOver => {
+                // betting round finished: return what wasn't called, move
+                // the rest to the pot and maybe deal the next street
+                self.close_bet_round(); 
+
+                let live = (0..self.table.seat_count())
+                    .filter(|&i| self.table.seat(i).is_some_and(|p| !p.folded))
+                    .count(); 
+                if live > 1 && !self.betting_open() {
+                    // all-in: no more betting, deal the rest and show down
+                    let _ = self.run_it(self.runouts); 
+                } else if live > 1 {
+                    self.deal_next_street(); 
+                } else {
+                    let board = self.board.clone(); 
+                    self.finish_hand(&[board]); // everyone else folded
                 }
             }
         }
+        Ok(outcome)
+    }
+
+}
+
+/// Burn a card and deal the next street: three cards for the flop, then one.
+fn deal_street(deck: &mut Deck, board: &mut Vec<Card>) {
+    deck.deal(); // at each betting round a card is discarded
+    let cards = if board.is_empty() { 3 } else { 1 }; 
+    for _ in 0..cards {
+        board.push(deck.deal().expect("deck ran out of cards")); 
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RakeCap, Rank, Suit};
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    #[test]
+    fn scenario_deals_fixed_hole_cards_and_flop() {
+        let table = Table::with_stacks(&[100; 3]);
+
+        let aces = [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)];
+        let flop = [
+            card(Rank::King, Suit::Clubs),
+            card(Rank::Seven, Suit::Diamonds),
+            card(Rank::Two, Suit::Spades),
+        ];
+        let scenario = Scenario::new().hole_cards(1, aces).board(&flop);
+
+        let mut game = GameState::new(table, 1);
+        game.start_scenario(&scenario).unwrap();
+        assert_eq!(game.table.seat(1).unwrap().hole_cards(), Some((aces[0], aces[1])));
+
+        game.deal_next_street();
+        assert_eq!(game.board, flop);
+        // the blinds went into the pot with the flop (less the big blind's
+        // uncalled chip), and every chip is paid out at showdown
+        assert_eq!(game.pot.total(), Chips(2));
+        for _ in 0..3 {
+            game.deal_next_street();
+        }
+        assert_eq!(game.street, Street::Showdown);
+        let total: Chips = (0..3).map(|i| game.table.seat(i).unwrap().chips).sum();
+        assert_eq!(total, Chips(300));
+
+        // bad scenarios are errors, not panics
+        let twice = Scenario::new().hole_cards(1, aces).board(&[aces[0]]);
+        let config = DeckConfig::default();
+        assert_eq!(twice.deck_for(&game.table, config).err(), Some(DeckError::CardNotInDeck(aces[0])));
+        let long = Scenario::new().board(&[flop[0]; 6]);
+        assert_eq!(long.deck_for(&game.table, config).err(), Some(DeckError::TooManyBoardCards(6)));
+        let absent = Scenario::new().hole_cards(5, aces);
+        assert_eq!(game.start_scenario(&absent), Err(DeckError::SeatNotDealtIn(5)));
+    }
+
+    #[test]
+    fn only_the_seat_to_act_has_legal_actions() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 3]), 1);
+        game.start_hand(); // button 1, SB 2, BB 0, seat 1 to act
+
+        assert_eq!(game.to_act, 1);
+        assert!(game.legal_actions(1).call.is_some());
+        assert_eq!(game.legal_actions(0), LegalActions::default());
+        assert_eq!(game.legal_actions(2), LegalActions::default());
+    }
+
+    #[test]
+    fn straddles_move_first_action_and_are_recorded() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 5]), 1);
+        game.straddle_rules = StraddleRules { first: Some(StraddleKind::Utg), max_restraddles: 1 };
+
+        // button moves to 1: SB 2, BB 3, UTG 4 straddles, 0 re-straddles
+        game.volunteer_straddle(4);
+        game.volunteer_straddle(0);
+        game.start_hand();
+
+        assert_eq!(game.to_act, 1);
+        assert_eq!(game.bet_round.as_ref().unwrap().current_bet(), Chips(8));
+        assert!(game.history.contains(&HandEvent::Straddle { seat: 4, amount: Chips(4) }));
+        assert!(game.history.contains(&HandEvent::Straddle { seat: 0, amount: Chips(8) }));
+        assert_eq!(game.legal_actions(1).raise, Some(Chips(8)..=Chips(92)));
+    }
+
+    #[test]
+    fn heads_up_button_posts_small_blind_and_acts_first_preflop() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 3]), 1);
+        game.start_hand(); // button 1, SB 2, BB 0
+
+        // the button busts; seat 0 must not post the big blind again
+        game.table.remove_player(1);
+        game.start_hand();
+        assert_eq!(game.table.dealer_button, 0);
+        assert_eq!(game.to_act, 0);
+        assert_eq!(game.bet_round.as_ref().unwrap().contributed(0), Chips(1));
+        assert_eq!(game.bet_round.as_ref().unwrap().contributed(2), Chips(2));
+
+        game.player_action(0, Action::Call).unwrap();
+        game.player_action(2, Action::Check).unwrap();
+        assert_eq!(game.street, Street::Flop);
+        assert_eq!(game.to_act, 2);
+    }
+
+    #[test]
+    fn returning_player_posts_the_blinds_they_missed() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 4]), 1);
+        game.button_rule = ButtonRule::Dead;
+        game.start_hand(); // button 1, SB 2, BB 3
+
+        game.table.seat_mut(0).unwrap().sitting_out = true;
+        game.start_hand(); // BB skips seat 0 and lands on 1
+        assert_eq!(game.positions().unwrap().big_blind, 1);
+        assert!(game.table.seat(0).unwrap().folded);
+
+        game.table.seat_mut(0).unwrap().sitting_out = false;
+        game.start_hand(); // button 3, SB 1, BB 2
+        assert!(game.history.contains(&HandEvent::Blind { seat: 0, amount: Chips(2) }));
+        assert!(game.history.contains(&HandEvent::DeadBlind { seat: 0, amount: Chips(1) }));
+        assert_eq!(game.bet_round.as_ref().unwrap().contributed(0), Chips(2));
+        assert!(!game.table.seat(0).unwrap().missed_big_blind);
+    }
+
+    #[test]
+    fn made_up_blinds_never_stack_on_other_blinds() {
+        // seat `owes` comes back owing both blinds; button 1, SB 2, BB 3, UTG 0
+        let start = |owes: usize| {
+            let mut game = GameState::new(Table::with_stacks(&[100; 4]), 1);
+            game.straddle_rules = StraddleRules { first: Some(StraddleKind::Utg), max_restraddles: 0 };
+            game.volunteer_straddle(owes);
+            let p = game.table.seat_mut(owes).unwrap();
+            p.missed_small_blind = true;
+            p.missed_big_blind = true;
+            game.start_hand();
+            game
+        };
+
+        // in the small blind the make-up only tops it up to the big blind
+        let game = start(2);
+        let round = game.bet_round.as_ref().unwrap();
+        assert_eq!((round.contributed(2), round.current_bet()), (Chips(2), Chips(2)));
+        assert!(!game.history.iter().any(|e| matches!(e, HandEvent::DeadBlind { .. })));
+
+        // in the big blind there is nothing to make up
+        let game = start(3);
+        assert_eq!(game.bet_round.as_ref().unwrap().contributed(3), Chips(2));
+        assert_eq!(game.table.seat(3).unwrap().chips, Chips(98));
+
+        // under the gun they post their blinds instead of straddling
+        let game = start(0);
+        let round = game.bet_round.as_ref().unwrap();
+        assert_eq!((round.contributed(0), round.current_bet()), (Chips(2), Chips(2)));
+        assert!(!game.history.iter().any(|e| matches!(e, HandEvent::Straddle { .. })));
+        assert_eq!(game.to_act, 0);
+        assert!(game.legal_actions(0).check);
+    }
+
+    #[test]
+    fn pot_goes_to_the_last_player_without_a_showdown() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 3]), 1);
+        game.start_hand(); // button 1, SB 2, BB 0
+
+        game.player_action(1, Action::Raise(Chips(4))).unwrap();
+        game.player_action(2, Action::Fold).unwrap();
+        game.player_action(0, Action::Fold).unwrap();
+
+        let result = game.result.as_ref().expect("hand is over");
+        assert_eq!(game.street, Street::Showdown);
+        assert!(result.shown.is_empty());
+        assert_eq!(result.won(1), Chips(5));
+        assert_eq!(game.table.seat(1).unwrap().chips, Chips(103));
+        assert!(game.history.contains(&HandEvent::UncalledBet { seat: 1, amount: Chips(4) }));
+    }
+
+    #[test]
+    fn decks_that_cannot_be_settled_are_refused() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 3]), 1);
+
+        let jokers = Deck::builder().config(DeckConfig::default().with_jokers(2)).build().unwrap();
+        assert_eq!(game.start_hand_with_deck(jokers), Err(DeckError::JokersNotSupported));
+        let mut short = Deck::new_shuffled();
+        for _ in 0..40 {
+            short.deal();
+        }
+        assert_eq!(
+            game.start_hand_with_deck(short),
+            Err(DeckError::NotEnoughCards { needed: 14, available: 12 })
+        );
+        assert!(game.positions().is_none());
+        assert!(game.bet_round.is_none());
+
+        assert_eq!(game.start_hand_with_deck(Deck::new_shuffled()), Ok(()));
+    }
+
+    #[test]
+    fn all_in_hand_can_be_run_twice() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 2]), 1);
+        game.start_hand(); // heads-up: button 1 posts the small blind
+
+        assert_eq!(game.run_it(2).unwrap_err(), ActionError::BettingNotOver);
+        game.runouts = 2;
+        game.player_action(1, Action::Call).unwrap();
+        game.player_action(0, Action::Check).unwrap();
+        game.player_action(0, Action::Allin).unwrap();
+        game.player_action(1, Action::Call).unwrap();
+
+        // betting is over, so the turn and river were run twice straight away
+        let result = game.result.as_ref().unwrap();
+        assert_eq!(result.boards.len(), 2);
+        assert_eq!(result.boards[0].len(), 5);
+        // the flop came before the all-in; the turn and river are run twice
+        assert_eq!(result.boards[0][..3], result.boards[1][..3]);
+        assert!(result.boards[0][3..].iter().all(|c| !result.boards[1].contains(c)));
+        assert_eq!(result.pots.iter().map(|p| p.amount).sum::<Chips>(), Chips(200));
+        assert!(result.pots.iter().all(|p| p.amount == Chips(100)));
+        assert_eq!(game.table.seat(0).unwrap().chips + game.table.seat(1).unwrap().chips, Chips(200));
+        assert_eq!(game.street, Street::Showdown);
+    }
+
+    #[test]
+    fn runouts_are_capped_by_the_cards_left() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 2]), 1);
+        game.runouts = 10;
+        game.start_hand();
+        game.player_action(1, Action::Allin).unwrap();
+        game.player_action(0, Action::Call).unwrap();
+
+        // 48 cards after the hole cards make six boards of burns plus five
+        let result = game.result.as_ref().unwrap();
+        assert_eq!(result.boards.len(), 6);
+        assert_eq!(result.pots.iter().map(|p| p.amount).sum::<Chips>(), Chips(200));
+
+        // nine-handed short deck has 18 cards left, enough for two boards
+        let mut game = GameState::new(Table::with_stacks(&[100; 9]), 1);
+        game.runouts = 3;
+        let short = DeckConfig::new(crate::DeckKind::ShortDeck);
+        game.configure_deck(short, Evaluator::new(crate::DeckKind::ShortDeck)).unwrap();
+        game.start_hand(); // button 1, SB 2, BB 3
+        while game.to_act != 2 {
+            game.player_action(game.to_act, Action::Fold).unwrap();
+        }
+        game.player_action(2, Action::Allin).unwrap();
+        game.player_action(3, Action::Call).unwrap();
+        assert_eq!(game.result.as_ref().unwrap().boards.len(), 2);
+    }
+
+    #[test]
+    fn runouts_only_pay_rake_for_streets_that_were_bet() {
+        let mut game = GameState::new(Table::with_stacks(&[100; 2]), 1);
+        game.rake = RakePolicy::percent(10).with_cap(RakeCap::PerStreet(Chips(3)));
+        game.runouts = 2;
+        game.start_hand();
+        game.player_action(1, Action::Allin).unwrap();
+        game.player_action(0, Action::Call).unwrap();
+
+        // all-in preflop: one street's cap, however many boards follow
+        let result = game.result.as_ref().unwrap();
+        assert_eq!(result.boards.len(), 2);
+        assert_eq!(result.rake.total, Chips(3));
+        assert_eq!(result.pots.iter().map(|p| p.amount).sum::<Chips>(), Chips(197));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn board_runs_out_once_nobody_can_bet() {
+        let mut game = GameState::new(Table::with_stacks(&[100, 100, 30]), 1);
+        game.start_hand(); // button 1, SB 2, BB 0
+
+        game.player_action(1, Action::Allin).unwrap();
+        game.player_action(2, Action::Allin).unwrap();
+        game.player_action(0, Action::Call).unwrap();
+
+        assert_eq!(game.street, Street::Showdown);
+        assert_eq!(game.board.len(), 5);
+        assert_eq!(game.player_action(0, Action::Check), Err(ActionError::HandOver));
+        let total: Chips = (0..3).map(|i| game.table.seat(i).unwrap().chips).sum();
+        assert_eq!(total, Chips(230));
+    }
+
+    #[test]
+    fn every_player_acts_before_a_street_closes() {
+        let mut game = GameState::new(Table::with_stacks(&[100, 100, 100]), 1);
+        game.start_hand(); // button 1, SB 2, BB 0
+        game.player_action(1, Action::Call).unwrap();
+        game.player_action(2, Action::Call).unwrap();
+        game.player_action(0, Action::Check).unwrap();
+
+        // the first check doesn't end the flop
+        assert_eq!(game.street, Street::Flop);
+        assert_eq!(game.player_action(2, Action::Check), Ok(ActionOutcome::NextToAct(0)));
+        assert_eq!(game.player_action(0, Action::Bet(Chips(4))), Ok(ActionOutcome::NextToAct(1)));
+        game.player_action(1, Action::Allin).unwrap();
+        game.player_action(2, Action::Fold).unwrap();
+        // seat 0 must still answer the all-in; the all-in player is never asked
+        assert_eq!(game.to_act, 0);
+        assert_eq!(game.player_action(1, Action::Check), Err(ActionError::NotYourTurn { to_act: 0 }));
+        game.player_action(0, Action::Call).unwrap();
+        assert_eq!(game.street, Street::Showdown);
+    }
+
+    #[test]
+    fn river_bettor_shows_first_and_losers_may_muck() {
+        let mut table = Table::with_stacks(&[100; 3]);
+        table.seat_mut(1).unwrap().auto_muck = true;
+        let scenario = Scenario::new()
+            .hole_cards(0, [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)])
+            .hole_cards(1, [card(Rank::Seven, Suit::Spades), card(Rank::Seven, Suit::Hearts)])
+            .hole_cards(2, [card(Rank::Eight, Suit::Spades), card(Rank::Eight, Suit::Hearts)])
+            .board(&[
+                card(Rank::Two, Suit::Clubs),
+                card(Rank::Five, Suit::Diamonds),
+                card(Rank::Nine, Suit::Hearts),
+                card(Rank::Jack, Suit::Spades),
+                card(Rank::Three, Suit::Clubs),
+            ]);
+        let mut game = GameState::new(table, 1);
+        game.start_scenario(&scenario).unwrap(); // button 1, SB 2, BB 0
+        game.player_action(1, Action::Call).unwrap();
+        game.player_action(2, Action::Call).unwrap();
+        game.player_action(0, Action::Check).unwrap();
+        for _ in 0..2 {
+            for seat in [2, 0, 1] {
+                game.player_action(seat, Action::Check).unwrap();
+            }
+        }
+        game.player_action(2, Action::Check).unwrap();
+        game.player_action(0, Action::Bet(Chips(10))).unwrap();
+        assert_eq!(game.showdown_order(), vec![0, 1, 2]);
+        game.choose_at_showdown(2, ShowdownChoice::Show);
+        game.player_action(1, Action::Call).unwrap();
+        game.player_action(2, Action::Call).unwrap();
+
+        // the bettor's aces are shown first; the sevens can't beat them and
+        // are auto-mucked, the eights are shown by choice
+        let reveals: Vec<&HandEvent> = game
+            .history
+            .iter()
+            .filter(|e| matches!(e, HandEvent::Shown { .. } | HandEvent::Mucked { .. }))
+            .collect();
+        assert!(matches!(reveals[..], [HandEvent::Shown { seat: 0, .. }, HandEvent::Mucked { seat: 1 }, HandEvent::Shown { seat: 2, .. }]));
+        let result = game.result.as_ref().unwrap();
+        assert_eq!(result.mucked, vec![1]);
+        assert_eq!(result.shown.iter().map(|h| h.seat).collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(result.won(0), Chips(36));
+    }
+}
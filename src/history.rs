@@ -0,0 +1,35 @@
+//! history.rs - what happened during a hand, in order
+
+use crate::{Action, Card, Chips, Street};
+
+/// One entry in a hand history.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HandEvent {
+    /// A new hand started with the dealer button at `button`.
+    HandStarted { button: usize },
+    /// An ante, either for the player or on behalf of the table.
+    Ante { seat: usize, amount: Chips },
+    /// A small or big blind (what was actually posted, if short).
+    Blind { seat: usize, amount: Chips },
+    /// A missed small blind made up on return; it goes in the pot but
+    /// doesn't count towards the player's bet.
+    DeadBlind { seat: usize, amount: Chips },
+    /// A voluntary straddle posted before the cards were dealt.
+    Straddle { seat: usize, amount: Chips },
+    /// A player action that was accepted.
+    Action { seat: usize, action: Action },
+    /// The rest of the board for one runout of a hand run more than once.
+    Runout { run: usize, cards: Vec<Card> },
+    /// The part of a bet nobody called, handed back when the round ended.
+    UncalledBet { seat: usize, amount: Chips },
+    /// Board cards dealt for a new street.
+    Board { street: Street, cards: Vec<Card> },
+    /// Rake taken by the house before the pots were paid.
+    Rake { amount: Chips },
+    /// Hole cards turned face up at showdown.
+    Shown { seat: usize, cards: (Card, Card) },
+    /// A hand thrown away at showdown without being shown.
+    Mucked { seat: usize },
+    /// Chips won from one pot at the end of the hand.
+    Won { seat: usize, amount: Chips },
+}
@@ -1,14 +1,30 @@
 mod betting; 
+mod chips;
 mod player;
 mod table; 
 mod evaluator; 
 mod game; 
+mod fair;
+mod history;
+mod blinds;
+mod pot;
+mod showdown;
+mod rake;
+mod session;
 
-pub use betting::BetRound; 
+pub use betting::{ActionError, ActionOutcome, BetRound, BetStructure, FixedLimit, LegalActions, RaiseRules}; 
+pub use chips::{Chips, Denomination, Stakes};
 pub use player::{Action, Player};
 pub use table::Table; 
-pub use evaluator::{evaluate_seven, HandCategory, HandRank}; 
-pub use game::{GameState, Street}; 
+pub use evaluator::{evaluate_seven, Evaluator, HandCategory, HandRank, WildCards, WildHand}; 
+pub use game::{Antes, GameState, Scenario, StraddleKind, StraddleRules, Street}; 
+pub use history::HandEvent;
+pub use blinds::{ButtonRule, Positions};
+pub use pot::{Pot, SidePot};
+pub use rake::{Rake, RakeCap, RakePolicy};
+pub use showdown::{settle, HandResult, OddChipRule, PotResult, ShowdownChoice, ShownHand};
+pub use session::{BustRule, Session, StopConditions, StopReason};
+pub use fair::{verify_shuffle, Commitment, FairShuffle, FairnessError, Reveal};
 
 use rand::{seq::SliceRandom, thread_rng}; 
 use std::fmt;
@@ -36,6 +52,27 @@ pub enum Rank {
     Queen,
     King,
     Ace,
+    /// Only found in decks configured with jokers; never part of a standard hand.
+    Joker,
+}
+
+impl Rank {
+    /// The thirteen natural ranks, lowest first.
+    pub const NATURAL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -44,8 +81,22 @@ pub struct Card {
     pub suit: Suit,
 }
 
+impl Card {
+    /// The black joker (jokers carry a suit only to tell the two apart).
+    pub const BLACK_JOKER: Card = Card { rank: Rank::Joker, suit: Suit::Spades };
+    /// The red joker.
+    pub const RED_JOKER: Card = Card { rank: Rank::Joker, suit: Suit::Hearts };
+
+    pub fn is_joker(&self) -> bool {
+        self.rank == Rank::Joker
+    }
+}
+
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_joker() {
+            return write!(f, "Joker");
+        }
         let suit_symbol = match self.suit {
             Suit::Clubs => "♣",
             Suit::Diamonds => "♦",
@@ -56,7 +107,99 @@ impl fmt::Display for Card {
     }
 }
 
-/// A standard 52‑card deck.
+/// Which ranks a deck is built from.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum DeckKind {
+    /// 52 cards, Two to Ace.
+    #[default]
+    Standard,
+    /// 36 cards, Six to Ace.
+    ShortDeck,
+    /// 32 cards, Seven to Ace.
+    Piquet,
+}
+
+impl DeckKind {
+    /// The lowest natural rank in this deck.
+    pub fn lowest_rank(self) -> Rank {
+        match self {
+            DeckKind::Standard => Rank::Two,
+            DeckKind::ShortDeck => Rank::Six,
+            DeckKind::Piquet => Rank::Seven,
+        }
+    }
+
+    /// The natural ranks in this deck, lowest first.
+    pub fn ranks(self) -> impl Iterator<Item = Rank> {
+        Rank::NATURAL.into_iter().filter(move |&r| r >= self.lowest_rank())
+    }
+}
+
+/// What goes into a deck: the kind of pack, how many jokers per pack, and how
+/// many packs are shuffled together (a shoe).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DeckConfig {
+    pub kind: DeckKind,
+    pub jokers: u8,
+    pub decks: u8,
+}
+
+impl Default for DeckConfig {
+    fn default() -> Self {
+        Self::new(DeckKind::Standard)
+    }
+}
+
+impl DeckConfig {
+    /// A single pack of `kind` with no jokers.
+    pub fn new(kind: DeckKind) -> Self {
+        Self { kind, jokers: 0, decks: 1 }
+    }
+
+    /// Add one or two jokers to each pack.
+    pub fn with_jokers(mut self, jokers: u8) -> Self {
+        self.jokers = jokers;
+        self
+    }
+
+    /// Shuffle `decks` packs together.
+    pub fn shoe(mut self, decks: u8) -> Self {
+        self.decks = decks;
+        self
+    }
+
+    /// Total number of cards.
+    pub fn size(&self) -> usize {
+        (self.kind.ranks().count() * 4 + self.jokers as usize) * self.decks as usize
+    }
+
+    /// Reject compositions that make no sense on their own.
+    pub fn validate(&self) -> Result<(), DeckError> {
+        if self.jokers > 2 {
+            return Err(DeckError::TooManyJokers(self.jokers));
+        }
+        if self.decks == 0 {
+            return Err(DeckError::NoDecks);
+        }
+        Ok(())
+    }
+
+    /// Every card in a fixed (unshuffled) order.
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards = Vec::with_capacity(self.size()); 
+        for _ in 0..self.decks {
+            for &suit in &[Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades] {
+                for rank in self.kind.ranks() {
+                    cards.push(Card { rank, suit }); 
+                }
+            }
+            cards.extend([Card::BLACK_JOKER, Card::RED_JOKER].into_iter().take(self.jokers as usize));
+        }
+        cards
+    }
+}
+
+/// A shuffled deck; a standard 52 cards unless built from a `DeckConfig`.
 pub struct Deck {
     cards: Vec<Card>,
 }
@@ -64,39 +207,132 @@ pub struct Deck {
 impl Deck {
     /// Returns a shuffled deck. 
     pub fn new_shuffled() -> Self {
-        let mut cards = Vec::with_capacity(52); 
-        for &suit in &[Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades] {
-            for rank_val in 2u8..=14 {
-                let rank = match rank_val {
-                    2 => Rank::Two,
-                    3 => Rank::Three,
-                    4 => Rank::Four,
-                    5 => Rank::Five,
-                    6 => Rank::Six,
-                    7 => Rank::Seven,
-                    8 => Rank::Eight,
-                    9 => Rank::Nine,
-                    10 => Rank::Ten,
-                    11 => Rank::Jack,
-                    12 => Rank::Queen,
-                    13 => Rank::King,
-                    14 => Rank::Ace,
-                    _ => unreachable!(), // TODO: I don't think this is necessary
-                };
-                cards.push(Card { rank, suit }); 
-            }
-        }
+        Self::with_config(&DeckConfig::default())
+    }
 
+    /// Returns a shuffled deck of the given composition.
+    pub fn with_config(config: &DeckConfig) -> Self {
+        let mut cards = config.cards(); 
         cards.shuffle(&mut thread_rng()); 
         Self { cards } 
     }
 
+    /// Start building a deck with known cards removed or stacked on top.
+    pub fn builder() -> DeckBuilder {
+        DeckBuilder::default()
+    }
+
     /// Pops one card off the top; returns `None` when empty. 
     pub fn deal(&mut self) -> Option<Card> {
         self.cards.pop() 
     }
+
+    /// How many cards are left to deal.
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+}
+
+/// Builds a deck for a specific situation: dead cards are taken out, and the
+/// first cards to be dealt can be fixed in order. Everything else is shuffled.
+#[derive(Clone, Debug, Default)]
+pub struct DeckBuilder {
+    config: DeckConfig,
+    dead: Vec<Card>,
+    stacked: Vec<Option<Card>>,
+}
+
+impl DeckBuilder {
+    /// Build from this composition instead of a standard deck.
+    pub fn config(mut self, config: DeckConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Take a card out of the deck entirely (e.g. a card known to be mucked).
+    pub fn remove(mut self, card: Card) -> Self {
+        self.dead.push(card);
+        self
+    }
+
+    /// Fix the order of the next cards dealt, top first. A `None` entry is
+    /// filled with a random card, which is how burns and unknown cards are skipped.
+    pub fn stack(mut self, order: impl IntoIterator<Item = Option<Card>>) -> Self {
+        self.stacked.extend(order);
+        self
+    }
+
+    /// Shuffle the remaining cards and lay the stacked ones on top. Fails if
+    /// a card is stacked or removed more times than the deck holds it (once,
+    /// unless the config is a shoe), or more cards are stacked than there are.
+    pub fn build(self) -> Result<Deck, DeckError> {
+        let mut pool = self.config.cards();
+        if self.dead.len() + self.stacked.len() > pool.len() {
+            let needed = self.dead.len() + self.stacked.len();
+            return Err(DeckError::NotEnoughCards { needed, available: pool.len() });
+        }
+        for &card in self.dead.iter().chain(self.stacked.iter().flatten()) {
+            let idx = pool.iter().position(|&c| c == card).ok_or(DeckError::CardNotInDeck(card))?;
+            pool.swap_remove(idx);
+        }
+        pool.shuffle(&mut thread_rng());
+
+        // `deal` pops from the back, so the stacked order goes on reversed
+        let mut top = Vec::with_capacity(self.stacked.len());
+        for slot in self.stacked {
+            match slot {
+                Some(card) => top.push(card),
+                None => top.push(pool.pop().expect("not enough cards left to stack")),
+            }
+        }
+        pool.extend(top.into_iter().rev());
+        Ok(Deck { cards: pool })
+    }
+}
+
+/// Why a deck can't be built or used.
+#[derive(Debug, Eq, PartialEq)]
+pub enum DeckError {
+    /// A pack holds at most two jokers.
+    TooManyJokers(u8),
+    /// A shoe needs at least one pack.
+    NoDecks,
+    /// The evaluator's rules are for a different kind of deck.
+    WrongKind { deck: DeckKind, evaluator: DeckKind },
+    /// The evaluator can't rank hands containing jokers.
+    JokersNotSupported,
+    /// More cards were asked for than the deck holds.
+    NotEnoughCards { needed: usize, available: usize },
+    /// A card was stacked or removed more times than the deck holds it,
+    /// e.g. the same card given as a hole card and on the board.
+    CardNotInDeck(Card),
+    /// A board has at most five cards.
+    TooManyBoardCards(usize),
+    /// Hole cards were given for a seat that isn't dealt in.
+    SeatNotDealtIn(usize),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::TooManyJokers(n) => write!(f, "{} jokers per deck (at most 2)", n),
+            DeckError::NoDecks => write!(f, "a shoe needs at least one deck"),
+            DeckError::WrongKind { deck, evaluator } => {
+                write!(f, "{:?} deck used with {:?} hand rankings", deck, evaluator)
+            }
+            DeckError::JokersNotSupported => write!(f, "evaluator does not support jokers"),
+            DeckError::NotEnoughCards { needed, available } => {
+                write!(f, "need {} cards but the deck only has {}", needed, available)
+            }
+            DeckError::CardNotInDeck(card) => write!(f, "{} is not left in the deck", card),
+            DeckError::TooManyBoardCards(n) => write!(f, "{} board cards (at most 5)", n),
+            DeckError::SeatNotDealtIn(seat) => write!(f, "seat {} is not dealt in", seat),
+        }
+    }
 }
 
+impl std::error::Error for DeckError {}
+
 #[cfg(test)]
 mod tests {
     use super::*; 
@@ -109,4 +345,48 @@ mod tests {
         }
         assert_eq!(seen.len(), 52); 
     }
+
+    #[test]
+    fn deck_configs_have_expected_sizes() {
+        let short = DeckConfig::new(DeckKind::ShortDeck);
+        assert_eq!(short.cards().len(), 36);
+        assert!(short.cards().iter().all(|c| c.rank >= Rank::Six));
+
+        assert_eq!(DeckConfig::new(DeckKind::Piquet).size(), 32);
+        assert_eq!(DeckConfig::default().with_jokers(2).cards().len(), 54);
+
+        let shoe = DeckConfig::default().with_jokers(1).shoe(2);
+        assert_eq!(Deck::with_config(&shoe).remaining(), 106);
+        assert_eq!(DeckConfig::default().with_jokers(3).validate(), Err(DeckError::TooManyJokers(3)));
+    }
+
+    #[test]
+    fn builder_removes_dead_and_deals_stacked_first() {
+        let ace = Card { rank: Rank::Ace, suit: Suit::Spades };
+        let king = Card { rank: Rank::King, suit: Suit::Hearts };
+        let deuce = Card { rank: Rank::Two, suit: Suit::Clubs };
+
+        let mut deck = Deck::builder()
+            .remove(deuce)
+            .stack([Some(ace), None, Some(king)])
+            .build()
+            .unwrap();
+        assert_eq!(deck.remaining(), 51);
+        assert_eq!(deck.deal(), Some(ace));
+        let random = deck.deal().unwrap();
+        assert!(random != ace && random != king && random != deuce);
+        assert_eq!(deck.deal(), Some(king));
+        while let Some(card) = deck.deal() {
+            assert!(card != deuce);
+        }
+    }
+
+    #[test]
+    fn builder_rejects_cards_that_are_not_left() {
+        let ace = Card { rank: Rank::Ace, suit: Suit::Spades };
+        let result = Deck::builder().remove(ace).stack([Some(ace)]).build();
+        assert_eq!(result.err(), Some(DeckError::CardNotInDeck(ace)));
+        let result = Deck::builder().stack(vec![None; 53]).build();
+        assert_eq!(result.err(), Some(DeckError::NotEnoughCards { needed: 53, available: 52 }));
+    }
 }
@@ -5,23 +5,30 @@ fn main() {
     table.add_player(Player::new(2, "Bob",   100));
     table.add_player(Player::new(3, "Carol", 50));
 
-    let mut game = GameState::new(table, 2);
-    game.start_hand();
+    let game = GameState::new(table, 2);
+    let stop = StopConditions { max_hands: Some(20), ..StopConditions::default() };
+    let mut session = Session::new(game).with_stop(stop);
 
+    // naive bot: min-bet every street, open-raise preflop, otherwise call
+    // or check
+    let reason = session.run(|game, seat| {
+        let legal = game.legal_actions(seat);
+        let unraised = game.bet_round.as_ref().is_some_and(|r| r.current_bet() == game.big_blind);
+        match (&legal.bet, &legal.raise) {
+            (Some(bet), _) => Action::Bet(*bet.start()),
+            (_, Some(raise)) if game.street == Street::Preflop && unraised => Action::Raise(*raise.start()),
+            _ if legal.check => Action::Check,
+            _ => Action::Call,
+        }
+    });
 
-    // TODO: 
-    // * built a bot that tries a naive strategy 
-
-
-
-    // pre-flop actions
-    game.player_action(game.to_act, Action::Call);                  // UTG limp
-    game.player_action(game.to_act, Action::Raise(6));              // SB raises to 8
-    game.player_action(game.to_act, Action::Allin);                 // BB shoves 50
-    game.player_action(game.to_act, Action::Call);                  // UTG calls full 50
-    game.player_action(game.to_act, Action::Call);                  // SB covers 50
-
-    // automatically deals flop, turn, river when rounds close
-    println!("Final board: {:?}", game.board);
-    println!("Pot: {}", game.pot);          // 150 chips total (with side-pots inside)
+    println!("Stopped after {} hands: {:?}", session.hands_played(), reason);
+    for seat in 0..session.game.table.seat_count() {
+        if let Some(p) = session.game.table.seat(seat) {
+            println!("{} has {}", p.name, session.game.denomination.format(p.chips));
+        }
+    }
+    for p in session.busted() {
+        println!("{} busted", p.name);
+    }
 }
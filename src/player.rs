@@ -1,36 +1,49 @@
-use crate::{Card}; 
+use crate::{Card, Chips}; 
 
 #[derive(Clone, Debug)]
 pub struct Player {
     pub id: usize, 
     pub name: String, 
-    pub chips: u32, 
+    pub chips: Chips, 
     hole_cards: [Option<Card>; 2],
     pub folded: bool,
     pub all_in: bool,
+    /// Keeps the seat but isn't dealt in.
+    pub sitting_out: bool,
+    /// Blinds that passed while sitting out, owed on return.
+    pub missed_small_blind: bool,
+    pub missed_big_blind: bool,
+    /// Throw away hands that can't win at showdown unless asked to show.
+    pub auto_muck: bool,
 }
 
-/// A no-limit Texas Hold'em action expressed in chips
-#[derive(Debug)]
+/// A Texas Hold'em action expressed in chips. `Bet` is the total put in,
+/// `Raise` the increment over the current bet; in fixed-limit both amounts
+/// are set by the structure.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Action {
     Fold,
     Check,
     Call,
-    Bet(u32),
-    Raise(u32), 
+    Bet(Chips),
+    Raise(Chips), 
     Allin, 
 }
 
 
 impl Player {
-    pub fn new(id: usize, name: impl Into<String>, chips: u32) -> Self {
+    pub fn new(id: usize, name: impl Into<String>, chips: impl Into<Chips>) -> Self {
         Self {
             id, 
             name: name.into(), 
-            chips,
+            chips: chips.into(),
             hole_cards: [None, None], 
             folded: false,
-            all_in: false
+            all_in: false,
+            sitting_out: false,
+            missed_small_blind: false,
+            missed_big_blind: false,
+            auto_muck: false,
         }
     }
 
@@ -45,9 +58,10 @@ impl Player {
         false
     }
 
+    /// Reset for a new hand. Players sitting out start it folded.
     pub fn clear_hand(&mut self) {
         self.hole_cards = [None, None]; 
-        self.folded = false;
+        self.folded = self.sitting_out;
         self.all_in = false;
     }
 
@@ -0,0 +1,115 @@
+//! pot.rs - every chip put in during a hand, and the pots it makes
+
+use crate::{Chips, Table};
+
+/// One pot and the seats that can win it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SidePot {
+    pub amount: Chips,
+    /// Players still in the hand who put in at least this pot's level.
+    pub eligible: Vec<usize>,
+}
+
+/// Ledger of what each seat has put in across every street of a hand,
+/// antes included, plus dead money nobody can claim a share of (table antes,
+/// dead blinds). Chips from players who folded stay in.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Pot {
+    contributed: Vec<Chips>,
+    dead: Chips,
+}
+
+impl Pot {
+    pub fn new(seat_count: usize) -> Self {
+        Self { contributed: vec![Chips::ZERO; seat_count], dead: Chips::ZERO }
+    }
+
+    /// Add chips `seat_idx` put in.
+    pub fn add(&mut self, seat_idx: usize, amount: Chips) {
+        if self.contributed.len() <= seat_idx {
+            self.contributed.resize(seat_idx + 1, Chips::ZERO);
+        }
+        self.contributed[seat_idx] += amount;
+    }
+
+    /// Add money that belongs to no seat; it plays in the main pot.
+    pub fn add_dead(&mut self, amount: Chips) {
+        self.dead += amount;
+    }
+
+    /// What `seat_idx` has put in this hand.
+    pub fn contributed(&self, seat_idx: usize) -> Chips {
+        self.contributed.get(seat_idx).copied().unwrap_or_default()
+    }
+
+    /// Seats the ledger has room for.
+    pub fn seat_count(&self) -> usize {
+        self.contributed.len()
+    }
+
+    pub fn total(&self) -> Chips {
+        self.contributed.iter().sum::<Chips>() + self.dead
+    }
+
+    /// Split the ledger into the main pot and side pots, smallest first. A
+    /// new pot starts at each amount an all-in player still in the hand put
+    /// in; folded players' chips count towards every level they reached.
+    pub fn side_pots(&self, table: &Table) -> Vec<SidePot> {
+        let live: Vec<usize> = (0..self.contributed.len())
+            .filter(|&i| table.seat(i).is_some_and(|p| !p.folded))
+            .collect();
+        let mut levels: Vec<Chips> = live.iter().map(|&i| self.contributed[i]).filter(|c| !c.is_zero()).collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<SidePot> = Vec::new();
+        let mut below = Chips::ZERO;
+        for &level in &levels {
+            let amount = self
+                .contributed
+                .iter()
+                .map(|&c| c.min(level) - c.min(below))
+                .sum();
+            let eligible = live.iter().copied().filter(|&i| self.contributed[i] >= level).collect();
+            pots.push(SidePot { amount, eligible });
+            below = level;
+        }
+
+        // folded chips above the last live level go to the last pot, dead
+        // money to the main pot
+        let over: Chips = self.contributed.iter().map(|&c| c.saturating_sub(below)).sum();
+        match pots.last_mut() {
+            Some(last) => last.amount += over,
+            None if !(over + self.dead).is_zero() => pots.push(SidePot { amount: over, eligible: live }),
+            None => return pots,
+        }
+        pots[0].amount += self.dead;
+        pots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folded_chips_stay_in_the_pots_they_reached() {
+        let mut table = Table::with_stacks(&[100, 100, 100, 100]);
+        table.seat_mut(0).unwrap().folded = true;
+
+        let mut pot = Pot::new(4);
+        // seat 0 folds after putting in 50 over two streets; 1 is all-in for
+        // 20, 2 for 80, and 3 covers
+        pot.add(0, Chips(10));
+        pot.add(0, Chips(40));
+        pot.add(1, Chips(20));
+        pot.add(2, Chips(80));
+        pot.add(3, Chips(80));
+        pot.add_dead(Chips(5));
+
+        let pots = pot.side_pots(&table);
+        assert_eq!(pots[0], SidePot { amount: Chips(85), eligible: vec![1, 2, 3] });
+        assert_eq!(pots[1], SidePot { amount: Chips(150), eligible: vec![2, 3] });
+        assert_eq!(pots.iter().map(|p| p.amount).sum::<Chips>(), pot.total());
+    }
+}
@@ -0,0 +1,143 @@
+//! rake.rs - the house's cut of each pot in a cash game
+
+use crate::{Chips, Pot, SidePot};
+
+/// Most rake a hand can pay.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum RakeCap {
+    #[default]
+    Uncapped,
+    /// At most this much per hand.
+    PerHand(Chips),
+    /// At most this much for each betting street the hand reached, so a
+    /// hand that ends on the flop pays up to twice this.
+    PerStreet(Chips),
+}
+
+/// How rake is taken. The default takes none.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RakePolicy {
+    /// Share of each pot taken, in hundredths of a percent (500 = 5%).
+    pub basis_points: u32,
+    pub cap: RakeCap,
+    /// Caps by stake as `(big_blind, cap)`: the entry with the biggest big
+    /// blind not above the game's replaces `cap`.
+    pub stake_caps: Vec<(Chips, RakeCap)>,
+    /// Hands that end before the flop pay no rake.
+    pub no_flop_no_drop: bool,
+}
+
+/// Rake taken from one hand.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Rake {
+    pub total: Chips,
+    /// Taken from each pot, main pot first.
+    pub per_pot: Vec<Chips>,
+    /// `(seat, share)` of the rake in proportion to what each seat put in,
+    /// for working out rakeback.
+    pub by_seat: Vec<(usize, Chips)>,
+}
+
+impl RakePolicy {
+    /// Take `percent`% of each pot with no cap.
+    pub fn percent(percent: u32) -> Self {
+        Self { basis_points: percent * 100, ..Self::default() }
+    }
+
+    pub fn with_cap(mut self, cap: RakeCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// The cap that applies at a `big_blind` stake.
+    pub fn cap_for(&self, big_blind: Chips) -> RakeCap {
+        self.stake_caps
+            .iter()
+            .filter(|&&(bb, _)| bb <= big_blind)
+            .max_by_key(|&&(bb, _)| bb)
+            .map_or(self.cap, |&(_, cap)| cap)
+    }
+
+    /// Take rake out of `pots` (main pot first, so it is raked before the side
+    /// pots when the cap runs out). `streets` is how many betting streets the
+    /// hand reached, 1 for preflop only.
+    pub fn take(&self, pots: &mut [SidePot], ledger: &Pot, streets: u32, big_blind: Chips) -> Rake {
+        let mut rake = Rake::default();
+        if self.no_flop_no_drop && streets < 2 {
+            return rake;
+        }
+
+        let mut left = match self.cap_for(big_blind) {
+            RakeCap::Uncapped => Chips::MAX,
+            RakeCap::PerHand(cap) => cap,
+            RakeCap::PerStreet(cap) => cap * streets,
+        };
+        for pot in pots.iter_mut() {
+            let taken = Chips((pot.amount.0 as u64 * self.basis_points as u64 / 10_000) as u32).min(left);
+            pot.amount -= taken;
+            left -= taken;
+            rake.per_pot.push(taken);
+            rake.total += taken;
+        }
+
+        // split in proportion to contributions; rounding leftovers go to the
+        // biggest contributors
+        let put_in: Vec<(usize, Chips)> = (0..ledger.seat_count())
+            .map(|seat| (seat, ledger.contributed(seat)))
+            .filter(|(_, c)| !c.is_zero())
+            .collect();
+        let total_in: u64 = put_in.iter().map(|&(_, c)| c.0 as u64).sum();
+        if total_in == 0 {
+            return rake;
+        }
+        let mut by_seat: Vec<(usize, Chips)> = put_in
+            .iter()
+            .map(|&(seat, c)| (seat, Chips((rake.total.0 as u64 * c.0 as u64 / total_in) as u32)))
+            .collect();
+        let mut short = rake.total - by_seat.iter().map(|&(_, r)| r).sum::<Chips>();
+        let mut order: Vec<usize> = (0..put_in.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(put_in[i].1));
+        for i in order.into_iter().cycle() {
+            if short.is_zero() {
+                break;
+            }
+            by_seat[i].1 += Chips(1);
+            short -= Chips(1);
+        }
+        rake.by_seat = by_seat;
+        rake
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pots(amounts: &[u32]) -> Vec<SidePot> {
+        amounts.iter().map(|&amount| SidePot { amount: Chips(amount), eligible: vec![0, 1] }).collect()
+    }
+
+    #[test]
+    fn rake_is_capped_and_split_by_contribution() {
+        let mut ledger = Pot::new(3);
+        ledger.add(0, Chips(100));
+        ledger.add(1, Chips(100));
+        ledger.add(2, Chips(50));
+
+        let policy = RakePolicy::percent(5).with_cap(RakeCap::PerHand(Chips(10)));
+        let mut side_pots = pots(&[150, 100]);
+        let rake = policy.take(&mut side_pots, &ledger, 4, Chips(2));
+        assert_eq!(rake.per_pot, vec![Chips(7), Chips(3)]);
+        assert_eq!(rake.total, Chips(10));
+        assert_eq!(side_pots[0].amount + side_pots[1].amount, Chips(240));
+        assert_eq!(rake.by_seat, vec![(0, Chips(4)), (1, Chips(4)), (2, Chips(2))]);
+
+        // no flop, no drop; bigger stakes get a bigger cap
+        let mut policy = policy;
+        policy.no_flop_no_drop = true;
+        policy.stake_caps = vec![(Chips(10), RakeCap::PerStreet(Chips(20)))];
+        assert_eq!(policy.take(&mut pots(&[150]), &ledger, 1, Chips(2)).total, Chips(0));
+        assert_eq!(policy.cap_for(Chips(20)), RakeCap::PerStreet(Chips(20)));
+        assert_eq!(policy.take(&mut pots(&[1000]), &ledger, 2, Chips(20)).total, Chips(40));
+    }
+}
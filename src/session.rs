@@ -0,0 +1,181 @@
+//! session.rs - playing hand after hand at one table until it's time to stop
+
+use std::time::{Duration, Instant};
+
+use crate::{Action, GameState, HandResult, Player};
+
+/// What happens to a player who loses all their chips.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum BustRule {
+    /// They leave the table and the seat is freed.
+    #[default]
+    Remove,
+    /// They keep the seat but sit out until topped up.
+    SitOut,
+}
+
+/// Limits on how long a session runs. It always stops once fewer than two
+/// players with chips are left.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct StopConditions {
+    pub max_hands: Option<u32>,
+    /// Checked between hands; a hand in progress is always finished.
+    pub max_duration: Option<Duration>,
+}
+
+/// Why a session stopped.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StopReason {
+    HandLimit,
+    /// Fewer than two players with chips are left.
+    OnePlayerLeft,
+    TimeUp,
+}
+
+/// Plays hands one after another on a `GameState`, asking an action source
+/// what each player does. The button and blinds move on as usual between
+/// hands, and busted players are dealt with under `bust_rule`.
+pub struct Session {
+    pub game: GameState,
+    pub stop: StopConditions,
+    pub bust_rule: BustRule,
+    hands_played: u32,
+    started: Instant,
+    /// Players who left the table, in the order they busted.
+    busted: Vec<Player>,
+}
+
+impl Session {
+    pub fn new(game: GameState) -> Self {
+        Self {
+            game,
+            stop: StopConditions::default(),
+            bust_rule: BustRule::default(),
+            hands_played: 0,
+            started: Instant::now(),
+            busted: Vec::new(),
+        }
+    }
+
+    pub fn with_stop(mut self, stop: StopConditions) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    pub fn with_bust_rule(mut self, rule: BustRule) -> Self {
+        self.bust_rule = rule;
+        self
+    }
+
+    pub fn hands_played(&self) -> u32 {
+        self.hands_played
+    }
+
+    /// Players removed after busting, first out first.
+    pub fn busted(&self) -> &[Player] {
+        &self.busted
+    }
+
+    /// Why the session should stop now, if it should.
+    pub fn should_stop(&self) -> Option<StopReason> {
+        let table = &self.game.table;
+        let players = (0..table.seat_count())
+            .filter(|&i| table.is_dealt_in(i) && table.seat(i).is_some_and(|p| !p.chips.is_zero()))
+            .count();
+        if players < 2 {
+            Some(StopReason::OnePlayerLeft)
+        } else if self.stop.max_hands.is_some_and(|max| self.hands_played >= max) {
+            Some(StopReason::HandLimit)
+        } else if self.stop.max_duration.is_some_and(|max| self.started.elapsed() >= max) {
+            Some(StopReason::TimeUp)
+        } else {
+            None
+        }
+    }
+
+    /// Play hands until a stop condition is met. `actions` is asked for
+    /// the action of the seat to act each time (see `play_hand`).
+    pub fn run(&mut self, mut actions: impl FnMut(&GameState, usize) -> Action) -> StopReason {
+        self.bust_out();
+        loop {
+            if let Some(reason) = self.should_stop() {
+                return reason;
+            }
+            self.play_hand(&mut actions);
+        }
+    }
+
+    /// Play one hand to the end and deal with anyone who busted. An illegal
+    /// action from `actions` is treated like a player timing out: they check
+    /// if they can, otherwise they fold.
+    pub fn play_hand(&mut self, actions: &mut impl FnMut(&GameState, usize) -> Action) -> &HandResult {
+        self.bust_out();
+        self.game.start_hand();
+        while self.game.result.is_none() {
+            let seat = self.game.to_act;
+            let action = actions(&self.game, seat);
+            if self.game.player_action(seat, action).is_err() {
+                let timeout = if self.game.legal_actions(seat).check { Action::Check } else { Action::Fold };
+                self.game.player_action(seat, timeout).expect("checking or folding is always allowed");
+            }
+        }
+        self.hands_played += 1;
+        self.bust_out();
+        self.game.result.as_ref().expect("hand is over")
+    }
+
+    /// Remove or sit out everyone dealt in with no chips left.
+    fn bust_out(&mut self) {
+        let table = &mut self.game.table;
+        for seat in 0..table.seat_count() {
+            if !table.is_dealt_in(seat) || table.seat(seat).is_some_and(|p| !p.chips.is_zero()) {
+                continue;
+            }
+            match self.bust_rule {
+                BustRule::Remove => self.busted.extend(table.remove_player(seat)),
+                BustRule::SitOut => {
+                    if let Some(p) = table.seat_mut(seat) {
+                        p.sitting_out = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Chips, Table};
+
+    fn total_chips(session: &Session) -> Chips {
+        let table = &session.game.table;
+        (0..table.seat_count()).filter_map(|i| table.seat(i)).map(|p| p.chips).sum()
+    }
+
+    #[test]
+    fn shoving_every_hand_plays_down_to_one_player() {
+        let mut session = Session::new(GameState::new(Table::with_stacks(&[50, 100, 150]), 1));
+        let reason = session.run(|_, _| Action::Allin);
+
+        assert_eq!(reason, StopReason::OnePlayerLeft);
+        assert_eq!(session.busted().len(), 2);
+        assert_eq!(session.game.table.active_player_count(), 1);
+        assert_eq!(total_chips(&session), Chips(300));
+    }
+
+    #[test]
+    fn stops_after_the_hand_limit_and_sits_out_busted_players() {
+        let stop = StopConditions { max_hands: Some(5), ..StopConditions::default() };
+        let game = GameState::new(Table::with_stacks(&[100, 100, 0]), 1);
+        let mut session = Session::new(game).with_stop(stop).with_bust_rule(BustRule::SitOut);
+
+        // raising nothing is illegal, so everyone checks or folds instead
+        let reason = session.run(|_, _| Action::Raise(Chips::ZERO));
+        assert_eq!(reason, StopReason::HandLimit);
+        assert_eq!(session.hands_played(), 5);
+        assert!(session.game.table.seat(2).unwrap().sitting_out);
+        assert!(session.busted().is_empty());
+        assert_eq!(total_chips(&session), Chips(200));
+    }
+}
@@ -0,0 +1,246 @@
+//! showdown.rs - deciding who wins each pot and paying them
+
+use crate::{Card, Chips, DeckError, Evaluator, HandRank, Rake, Rank, SidePot, Suit, Table};
+
+/// Who gets the chips left over when a pot doesn't split evenly.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum OddChipRule {
+    /// The first winner left of the button, then the next, and so on.
+    #[default]
+    LeftOfButton,
+    /// The winner holding the highest hole card, suits breaking ties
+    /// (spades, hearts, diamonds, clubs).
+    HighCardBySuit,
+    /// Split in chips of this size, the smallest denomination on the table;
+    /// whole odd chips go left of the button as usual.
+    SmallestDenomination(Chips),
+}
+
+/// What a player does at showdown with a hand that can't win. Hands that
+/// can still win are always shown.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ShowdownChoice {
+    Show,
+    Muck,
+}
+
+/// A hand turned over at showdown.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShownHand {
+    pub seat: usize,
+    /// Which board it was ranked on when the hand was run more than once.
+    pub run: usize,
+    pub cards: (Card, Card),
+    pub rank: HandRank,
+}
+
+/// One pot and how it was split.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PotResult {
+    /// Which board this share of the pot was played on (0 unless run twice).
+    pub run: usize,
+    pub amount: Chips,
+    /// `(seat, chips won)` for everyone with a share.
+    pub winners: Vec<(usize, Chips)>,
+}
+
+/// How a hand ended.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HandResult {
+    /// Main pot first, then side pots.
+    pub pots: Vec<PotResult>,
+    /// Hands shown down, in showdown order; empty when everyone else folded.
+    pub shown: Vec<ShownHand>,
+    /// Seats that threw their hand away at showdown without showing it.
+    pub mucked: Vec<usize>,
+    /// What the house took before the pots were paid.
+    pub rake: Rake,
+    /// Every board the hand was played on; more than one when it was run
+    /// more than once, but fewer than asked if the deck ran short.
+    pub boards: Vec<Vec<Card>>,
+}
+
+impl HandResult {
+    /// Total chips `seat_idx` won.
+    pub fn won(&self, seat_idx: usize) -> Chips {
+        self.pots
+            .iter()
+            .flat_map(|p| &p.winners)
+            .filter(|&&(seat, _)| seat == seat_idx)
+            .map(|&(_, amount)| amount)
+            .sum()
+    }
+}
+
+/// Award every pot (after any rake) and pay the winners. With one player left the pot is
+/// theirs without showing; otherwise each live hand is ranked against the
+/// board and each pot goes to its best eligible hand(s). Odd chips from a
+/// split are handed out under `odd_chips`; every chip in the pot is paid.
+/// Fails without paying anyone if the evaluator can't rank a hand (a joker
+/// that isn't wild).
+pub fn settle(
+    side_pots: Vec<SidePot>,
+    table: &mut Table,
+    board: &[Card],
+    evaluator: &Evaluator,
+    odd_chips: OddChipRule,
+) -> Result<HandResult, DeckError> {
+    let live: Vec<usize> = (0..table.seat_count())
+        .filter(|&i| table.seat(i).is_some_and(|p| !p.folded))
+        .collect();
+
+    let mut shown = Vec::new();
+    if live.len() > 1 {
+        for &seat in &live {
+            let Some((a, b)) = table.seat(seat).and_then(|p| p.hole_cards()) else { continue };
+            let cards: Vec<Card> = [a, b].into_iter().chain(board.iter().copied()).collect();
+            let Ok(cards) = <[Card; 7]>::try_from(cards) else { continue };
+            let rank = evaluator.evaluate_seven(&cards)?;
+            shown.push(ShownHand { seat, run: 0, cards: (a, b), rank });
+        }
+    }
+
+    let mut pots = Vec::new();
+    for side_pot in side_pots {
+        let mut winners: Vec<usize> = if live.len() == 1 {
+            side_pot.eligible.clone()
+        } else {
+            let contenders: Vec<&ShownHand> = shown.iter().filter(|h| side_pot.eligible.contains(&h.seat)).collect();
+            match contenders.iter().map(|h| &h.rank).max() {
+                Some(best) => contenders.iter().filter(|h| &h.rank == best).map(|h| h.seat).collect(),
+                None => side_pot.eligible.clone(),
+            }
+        };
+        if winners.is_empty() {
+            continue;
+        }
+
+        // whoever is first in line gets the first odd chip
+        let n = table.seat_count();
+        let button = table.dealer_button;
+        winners.sort_by_key(|&seat| (seat + n - button - 1) % n);
+        if odd_chips == OddChipRule::HighCardBySuit {
+            winners.sort_by_key(|&seat| std::cmp::Reverse(high_card(table, seat)));
+        }
+        let unit = match odd_chips {
+            OddChipRule::SmallestDenomination(chip) => chip.max(Chips(1)),
+            _ => Chips(1),
+        };
+
+        let paid = split(side_pot.amount, &winners, unit);
+        for &(seat, amount) in &paid {
+            if let Some(p) = table.seat_mut(seat) {
+                p.chips += amount;
+            }
+        }
+        pots.push(PotResult { run: 0, amount: side_pot.amount, winners: paid });
+    }
+
+    Ok(HandResult { pots, shown, mucked: Vec::new(), rake: Rake::default(), boards: vec![board.to_vec()] })
+}
+
+/// Share `amount` between `winners` in chips of `unit`, odd chips going
+/// in order. Anything smaller than one chip goes to the first winner.
+fn split(amount: Chips, winners: &[usize], unit: Chips) -> Vec<(usize, Chips)> {
+    let units = amount.0 / unit.0;
+    let share = unit * (units / winners.len() as u32);
+    let odd = (units % winners.len() as u32) as usize;
+
+    let mut paid: Vec<(usize, Chips)> = winners.iter().map(|&seat| (seat, share)).collect();
+    for p in paid.iter_mut().take(odd) {
+        p.1 += unit;
+    }
+    paid[0].1 += amount % unit.0;
+    debug_assert_eq!(paid.iter().map(|p| p.1).sum::<Chips>(), amount);
+    paid
+}
+
+/// Highest hole card at `seat_idx` as (rank, suit) for the odd-chip rule.
+fn high_card(table: &Table, seat_idx: usize) -> Option<(Rank, u8)> {
+    let suit_order = |suit| match suit {
+        Suit::Clubs => 0,
+        Suit::Diamonds => 1,
+        Suit::Hearts => 2,
+        Suit::Spades => 3,
+    };
+    let (a, b) = table.seat(seat_idx)?.hole_cards()?;
+    [a, b].iter().map(|c| (c.rank, suit_order(c.suit))).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pot;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    #[test]
+    fn best_eligible_hand_wins_each_pot() {
+        let mut table = Table::with_stacks(&[0; 4]);
+        let hands = [
+            [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)],
+            [card(Rank::King, Suit::Spades), card(Rank::King, Suit::Hearts)],
+            [card(Rank::King, Suit::Clubs), card(Rank::King, Suit::Diamonds)],
+            [card(Rank::Queen, Suit::Clubs), card(Rank::Queen, Suit::Diamonds)],
+        ];
+        for (i, hand) in hands.iter().enumerate() {
+            table.seat_mut(i).unwrap().receive_card(hand[0]);
+            table.seat_mut(i).unwrap().receive_card(hand[1]);
+        }
+        let board = [
+            card(Rank::Two, Suit::Clubs),
+            card(Rank::Seven, Suit::Diamonds),
+            card(Rank::Nine, Suit::Hearts),
+            card(Rank::Jack, Suit::Spades),
+            card(Rank::Four, Suit::Clubs),
+        ];
+        table.seat_mut(3).unwrap().folded = true;
+
+        // the aces are all-in for 20; the kings split the 81 behind,
+        // including what the folded queens put in
+        let mut pot = Pot::new(4);
+        pot.add(0, Chips(20));
+        pot.add(1, Chips(50));
+        pot.add(2, Chips(50));
+        pot.add(3, Chips(41));
+        // a joker the evaluator can't rank stops the whole settlement
+        let mut joker_board = board;
+        joker_board[4] = Card::BLACK_JOKER;
+        let unrankable = settle(pot.side_pots(&table), &mut table, &joker_board, &Evaluator::default(), OddChipRule::LeftOfButton);
+        assert_eq!(unrankable, Err(DeckError::JokersNotSupported));
+        assert_eq!(table.seat(0).unwrap().chips, Chips::ZERO);
+
+        let result = settle(pot.side_pots(&table), &mut table, &board, &Evaluator::default(), OddChipRule::LeftOfButton).unwrap();
+
+        assert_eq!(result.shown.len(), 3);
+        assert_eq!(result.pots[0].winners, vec![(0, Chips(80))]);
+        // odd chip to seat 1, first left of the button at 0
+        assert_eq!(result.pots[1].winners, vec![(1, Chips(41)), (2, Chips(40))]);
+        assert_eq!(table.seat(2).unwrap().chips, Chips(40));
+        assert_eq!(result.won(1), Chips(41));
+    }
+
+    #[test]
+    fn odd_chip_rules_conserve_every_chip() {
+        // seats 2 and 0 tie, the button is on 0
+        let winners = [2, 0];
+        assert_eq!(split(Chips(7), &winners, Chips(1)), vec![(2, Chips(4)), (0, Chips(3))]);
+        assert_eq!(split(Chips(25), &winners, Chips(5)), vec![(2, Chips(15)), (0, Chips(10))]);
+        assert_eq!(split(Chips(27), &[2, 0, 1], Chips(5)), vec![(2, Chips(12)), (0, Chips(10)), (1, Chips(5))]);
+        assert_eq!(split(Chips(3), &winners, Chips(5)), vec![(2, Chips(3)), (0, Chips(0))]);
+
+        // both hold an ace; the ace of spades beats the ace of clubs
+        let mut table = Table::with_stacks(&[0; 2]);
+        let hands = [
+            [card(Rank::Ace, Suit::Clubs), card(Rank::King, Suit::Clubs)],
+            [card(Rank::Two, Suit::Hearts), card(Rank::Ace, Suit::Spades)],
+        ];
+        for (i, hand) in hands.iter().enumerate() {
+            table.seat_mut(i).unwrap().receive_card(hand[0]);
+            table.seat_mut(i).unwrap().receive_card(hand[1]);
+        }
+        assert!(high_card(&table, 1) > high_card(&table, 0));
+    }
+}
@@ -42,15 +42,24 @@ impl Table {
         self.seats.iter().filter(|s| s.is_some()).count()
     }
 
+    /// Seats that receive hole cards, in the order they are dealt to.
+    pub fn deal_order(&self) -> Vec<usize> {
+        (0..self.seats.len()).filter(|&i| self.is_dealt_in(i)).collect()
+    }
+
+    /// Whether someone sits at `idx` and isn't sitting out.
+    pub fn is_dealt_in(&self, idx: usize) -> bool {
+        self.seat(idx).is_some_and(|p| !p.sitting_out)
+    }
+
     /// Deal two hole-cards to every seated player who is not folded/all-in.
     pub fn deal_hole_cards(&mut self, deck: &mut Deck) {
         // Deal oen card to each, then the second (round-robin) to mimic real dealing. 
+        let order = self.deal_order();
         for _round in 0..2 {
-            for seat in &mut self.seats {
-                if let Some(p) = seat {
-                    let card = deck.deal().expect("Deck ran out of cards"); 
-                    p.receive_card(card); 
-                }
+            for &idx in &order {
+                let card = deck.deal().expect("Deck ran out of cards"); 
+                self.seats[idx].as_mut().unwrap().receive_card(card); 
             }
         }
     }
@@ -60,19 +69,20 @@ impl Table {
         let seat_cnt = self.seats.len(); 
         for offset in 1..=seat_cnt {
             let idx = (self.dealer_button + offset) % seat_cnt; 
-            if self.seats[idx].is_some() {
+            if self.is_dealt_in(idx) {
                 self.dealer_button = idx;
                 break;
             }
         }
     }
 
-    /// Return the next seat with a player, skipping empties. 
+    /// Return the next seat with a player, skipping empties and players
+    /// sitting out. 
     pub fn next_occupied(&self, from: usize) -> usize {
         let n = self.seats.len();
         (1..=n)
             .map(|i| (from + i) % n)
-            .find(|&i| self.seats[i].is_some())
+            .find(|&i| self.is_dealt_in(i))
             .unwrap()
     }
 
@@ -114,4 +124,21 @@ impl Table {
         println!();
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl Table {
+    /// Test fixture: one seat per stack, filled by players `p0`, `p1`, ...
+    pub(crate) fn with_stacks(stacks: &[u32]) -> Self {
+        Table::seated(stacks.len(), stacks)
+    }
+
+    /// Test fixture: `seats` seats with the first ones filled, one per stack.
+    pub(crate) fn seated(seats: usize, stacks: &[u32]) -> Self {
+        let mut table = Table::new(seats);
+        for (i, &chips) in stacks.iter().enumerate() {
+            table.add_player(Player::new(i, format!("p{}", i), chips));
+        }
+        table
+    }
+}